@@ -22,111 +22,62 @@ pub enum Camera {
 
 impl Camera {
     pub fn projection(&self) -> Matrix<f32, 4, 4> {
-        match self {
+        match *self {
             Camera::Perspective {
                 fov,
-                clip,
+                clip: (near, far),
                 aspect_ratio,
                 ..
-            } => {
-                let mut projection = Matrix::<f32, 4, 4>::identity();
-
-                let (near, far) = clip;
-
-                let focal_length = 1.0 / (fov / 2.0).tan();
-
-                projection[0][0] = focal_length / aspect_ratio;
-                projection[1][1] = -focal_length;
-                projection[2][2] = far / (near - far);
-                projection[2][3] = -1.0;
-                projection[3][2] = (near * far) / (near - far);
-
-                projection
-            }
+            } => Matrix::perspective(fov, aspect_ratio, near, far),
             Camera::Orthographic {
                 left,
                 right,
                 top,
                 bottom,
-                clip,
+                clip: (near, far),
                 ..
-            } => {
-                let mut projection = Matrix::<f32, 4, 4>::identity();
-
-                let (near, far) = clip;
-
-                projection[0][0] = 2.0 / (right - left);
-                projection[1][1] = 2.0 / (bottom - top);
-                projection[2][2] = 1.0 / (near - far);
-                projection[3][0] = -(right + left) / (right - left);
-                projection[3][1] = -(bottom + top) / (bottom - top);
-                projection[3][2] = near / (near - far);
+            } => Matrix::orthographic(left, right, top, bottom, near, far),
+        }
+    }
 
-                projection
-            }
+    pub fn inverse_projection(&self) -> Matrix<f32, 4, 4> {
+        match *self {
+            Camera::Perspective {
+                fov,
+                clip: (near, far),
+                aspect_ratio,
+                ..
+            } => Matrix::perspective_inverse(fov, aspect_ratio, near, far),
+            Camera::Orthographic {
+                left,
+                right,
+                top,
+                bottom,
+                clip: (near, far),
+                ..
+            } => Matrix::orthographic_inverse(left, right, top, bottom, near, far),
         }
     }
 
     pub fn view(&self) -> Matrix<f32, 4, 4> {
-        self.transform().inverse()
+        Matrix::trs_inverse(
+            self.get_position(),
+            self.get_orientation(),
+            Vector::new([1.0; 3]),
+        )
     }
 
     pub fn transform(&self) -> Matrix<f32, 4, 4> {
-        let position = match self {
-            Camera::Perspective { position, .. } => position,
-            Camera::Orthographic { position, .. } => position,
-        };
-
-        let mut transform = Matrix::identity();
-
-        transform = transform * self.roll();
-        transform = transform * self.pitch();
-        transform = transform * self.yaw();
-
-        transform[3][0] = position[0];
-        transform[3][1] = position[1];
-        transform[3][2] = position[2];
-
-        transform
+        Matrix::trs(
+            self.get_position(),
+            self.get_orientation(),
+            Vector::new([1.0; 3]),
+        )
     }
 
-    pub fn yaw(&self) -> Matrix<f32, 4, 4> {
-        let rotation = self.get_rotation();
-
-        let mut yaw = Matrix::<f32, 4, 4>::identity();
-
-        yaw[0][0] = rotation[2].cos();
-        yaw[1][0] = -rotation[2].sin();
-        yaw[0][1] = rotation[2].sin();
-        yaw[1][1] = rotation[2].cos();
-
-        yaw
-    }
-
-    pub fn pitch(&self) -> Matrix<f32, 4, 4> {
-        let rotation = self.get_rotation();
-
-        let mut pitch = Matrix::<f32, 4, 4>::identity();
-
-        pitch[0][0] = rotation[1].cos();
-        pitch[2][0] = rotation[1].sin();
-        pitch[0][2] = -rotation[1].sin();
-        pitch[2][2] = rotation[1].cos();
-
-        pitch
-    }
-
-    pub fn roll(&self) -> Matrix<f32, 4, 4> {
-        let rotation = self.get_rotation();
-
-        let mut roll = Matrix::<f32, 4, 4>::identity();
-
-        roll[1][1] = rotation[0].cos();
-        roll[2][1] = -rotation[0].sin();
-        roll[1][2] = rotation[0].sin();
-        roll[2][2] = rotation[0].cos();
-
-        roll
+    //rotation is roll, pitch and yaw about x, y and z
+    pub fn get_orientation(&self) -> Quaternion<f32> {
+        Quaternion::from_euler(self.get_rotation())
     }
 
     pub fn get_position(&self) -> Vector<f32, 3> {
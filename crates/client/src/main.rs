@@ -6,6 +6,7 @@ mod camera;
 
 use crate::camera::Camera;
 use common::bits::Bitset;
+use common::block;
 use common::convert::{Conversion, Convert, Region};
 use common::octree::SparseOctree;
 use common::voxel::Voxel;
@@ -44,6 +45,8 @@ const LOD: usize = 3;
 const PREPASS_SCALE: usize = 2;
 const MAX_BLOCKS: usize = 1024;
 const BLOCK_DETAIL: usize = 8;
+//the noise lattice is math::noise::hash of this seed, so the cpu can rebuild it
+const NOISE_SEED: u32 = 42069;
 
 pub type Vertex = (f32, f32, f32);
 pub type Color = [f32; 4];
@@ -77,50 +80,6 @@ fn root_path() -> Option<path::PathBuf> {
     Some(current_dir)
 }
 
-#[repr(u32)]
-#[derive(Debug)]
-enum Sound {
-    GrassWalk1 = 1,
-    GrassWalk2 = 2,
-    GrassWalk3 = 3,
-    GrassWalk4 = 4,
-    GrassWalk5 = 5,
-    GrassWalk6 = 6,
-    GrassWalk7 = 7,
-    GrassWalk8 = 8,
-    GrassWalk9 = 9,
-    GrassWalk10 = 10,
-    DirtWalk1 = 11,
-    DirtWalk2 = 12,
-    DirtWalk3 = 13,
-    DirtWalk4 = 14,
-    DirtWalk5 = 15,
-    DirtWalk6 = 16,
-    DirtWalk7 = 17,
-    DirtWalk8 = 18,
-    DirtWalk9 = 19,
-    DirtWalk10 = 20,
-    DirtWalk11 = 21,
-    StoneWalk1 = 22,
-    StoneWalk2 = 23,
-    StoneWalk3 = 24,
-    StoneWalk4 = 25,
-    StoneWalk5 = 26,
-    StoneWalk6 = 27,
-    StoneWalk7 = 28,
-    StoneWalk8 = 29,
-    StoneWalk9 = 30,
-    StoneWalk10 = 31,
-    StoneWalk11 = 32,
-}
-
-impl Sound {
-    fn name(&self) -> String {
-        use convert_case::{Case, Casing};
-        format!("{}.ogg", format!("{:?}", self).to_case(Case::Snake))
-    }
-}
-
 fn main() {
     println!("Hello, client!");
 
@@ -188,7 +147,10 @@ fn main() {
 
     let draw_pipeline = pipeline_compiler
         .create_graphics_pipeline(GraphicsPipelineInfo {
-            shaders: [Shader(Vertex, "fx", &[]), Shader(Fragment, "draw", &[])],
+            shaders: [
+                Shader(Vertex, "fx", block::DEFINES),
+                Shader(Fragment, "draw", block::DEFINES),
+            ],
             color: [gpu::prelude::Color {
                 format: Format::Rgba32Sfloat,
                 blend: None,
@@ -199,20 +161,23 @@ fn main() {
         .expect("failed to create pipeline");
     let resolve_pipeline = pipeline_compiler
         .create_compute_pipeline(ComputePipelineInfo {
-            shader: Shader(Compute, "resolve", &[]),
+            shader: Shader(Compute, "resolve", block::DEFINES),
             ..default()
         })
         .expect("failed to create pipeline");
     let history_pipeline = pipeline_compiler
         .create_compute_pipeline(ComputePipelineInfo {
-            shader: Shader(Compute, "history", &[]),
+            shader: Shader(Compute, "history", block::DEFINES),
             ..default()
         })
         .expect("failed to create pipeline");
 
     let upscale_pipeline = pipeline_compiler
         .create_graphics_pipeline(GraphicsPipelineInfo {
-            shaders: [Shader(Vertex, "fx", &[]), Shader(Fragment, "upscale", &[])],
+            shaders: [
+                Shader(Vertex, "fx", block::DEFINES),
+                Shader(Fragment, "upscale", block::DEFINES),
+            ],
             color: [gpu::prelude::Color {
                 format: device.presentation_format(swapchain.get()).unwrap(),
                 blend: None,
@@ -224,119 +189,119 @@ fn main() {
     
     let before_luminosity_pipeline = pipeline_compiler
         .create_compute_pipeline(ComputePipelineInfo {
-            shader: Shader(Compute, "before_luminosity", &[]),
+            shader: Shader(Compute, "before_luminosity", block::DEFINES),
             ..default()
         })
         .expect("failed to create pipeline");
     
     let after_luminosity_pipeline = pipeline_compiler
         .create_compute_pipeline(ComputePipelineInfo {
-            shader: Shader(Compute, "after_luminosity", &[]),
+            shader: Shader(Compute, "after_luminosity", block::DEFINES),
             ..default()
         })
         .expect("failed to create pipeline");
     
     let luminosity_pipeline = pipeline_compiler
         .create_compute_pipeline(ComputePipelineInfo {
-            shader: Shader(Compute, "build_luminosity", &[]),
+            shader: Shader(Compute, "build_luminosity", block::DEFINES),
             ..default()
         })
         .expect("failed to create pipeline");
     
     let input_pipeline = pipeline_compiler
         .create_compute_pipeline(ComputePipelineInfo {
-            shader: Shader(Compute, "input", &[]),
+            shader: Shader(Compute, "input", block::DEFINES),
             ..default()
         })
         .expect("failed to create pipeline");
 
     let box_pipeline = pipeline_compiler
         .create_compute_pipeline(ComputePipelineInfo {
-            shader: Shader(Compute, "build_box", &[]),
+            shader: Shader(Compute, "build_box", block::DEFINES),
             ..default()
         })
         .expect("failed to create pipeline");
     
     let build_struct_pipeline = pipeline_compiler
         .create_compute_pipeline(ComputePipelineInfo {
-            shader: Shader(Compute, "build_struct", &[]),
+            shader: Shader(Compute, "build_struct", block::DEFINES),
             ..default()
         })
         .expect("failed to create pipeline");
     
     let after_build_struct_pipeline = pipeline_compiler
         .create_compute_pipeline(ComputePipelineInfo {
-            shader: Shader(Compute, "after_build_struct", &[]),
+            shader: Shader(Compute, "after_build_struct", block::DEFINES),
             ..default()
         })
         .expect("failed to create pipeline");
     
     let build_blocks_pipeline = pipeline_compiler
         .create_compute_pipeline(ComputePipelineInfo {
-            shader: Shader(Compute, "build_blocks", &[]),
+            shader: Shader(Compute, "build_blocks", block::DEFINES),
             ..default()
         })
         .expect("failed to create pipeline");
 
     let build_world_pipeline = pipeline_compiler
         .create_compute_pipeline(ComputePipelineInfo {
-            shader: Shader(Compute, "build_world", &[]),
+            shader: Shader(Compute, "build_world", block::DEFINES),
             ..default()
         })
         .expect("failed to create pipeline");
     
     let build_bound_pipeline = pipeline_compiler
         .create_compute_pipeline(ComputePipelineInfo {
-            shader: Shader(Compute, "build_initial_bound", &[]),
+            shader: Shader(Compute, "build_initial_bound", block::DEFINES),
             ..default()
         })
         .expect("failed to create pipeline");
     
     let move_world_pipeline = pipeline_compiler
         .create_compute_pipeline(ComputePipelineInfo {
-            shader: Shader(Compute, "move_world", &[]),
+            shader: Shader(Compute, "move_world", block::DEFINES),
             ..default()
         })
         .expect("failed to create pipeline");
 
     let after_world_pipeline = pipeline_compiler
         .create_compute_pipeline(ComputePipelineInfo {
-            shader: Shader(Compute, "after_world", &[]),
+            shader: Shader(Compute, "after_world", block::DEFINES),
             ..default()
         })
         .expect("failed to create pipeline");
 
     /*let vertex_pipeline = pipeline_compiler
             .create_compute_pipeline(ComputePipelineInfo {
-                shader: Shader(Compute, "build_mesh", &[]),
+                shader: Shader(Compute, "build_mesh", block::DEFINES),
                 ..default()
             })
             .expect("failed to create pipeline");
     */
     let noise_pipeline = pipeline_compiler
         .create_compute_pipeline(ComputePipelineInfo {
-            shader: Shader(Compute, "build_noise", &[]),
+            shader: Shader(Compute, "build_noise", block::DEFINES),
             ..default()
         })
         .expect("failed to create pipeline");
 
     let perlin_pipeline = pipeline_compiler
         .create_compute_pipeline(ComputePipelineInfo {
-            shader: Shader(Compute, "build_perlin", &[]),
+            shader: Shader(Compute, "build_perlin", block::DEFINES),
             ..default()
         })
         .expect("failed to create pipeline");
 
     let worley_pipeline = pipeline_compiler
         .create_compute_pipeline(ComputePipelineInfo {
-            shader: Shader(Compute, "build_worley", &[]),
+            shader: Shader(Compute, "build_worley", block::DEFINES),
             ..default()
         })
         .expect("failed to create pipeline");
 
     let physics_pipeline = pipeline_compiler
         .create_compute_pipeline(ComputePipelineInfo {
-            shader: Shader(Compute, "physics", &[]),
+            shader: Shader(Compute, "physics", block::DEFINES),
             ..default()
         })
         .expect("failed to create pipeline");
@@ -705,7 +670,7 @@ fn main() {
 
     camera_info.set(CameraInfo {
         projection: camera.get().projection(),
-        inv_projection: camera.get().projection().inverse(),
+        inv_projection: camera.get().inverse_projection(),
         far: match camera.get() {
             Camera::Perspective { clip, .. } => clip.1,
             _ => unreachable!(),
@@ -1121,7 +1086,7 @@ fn main() {
 
                 camera_info.set(CameraInfo {
                     projection: camera.get().projection(),
-                    inv_projection: camera.get().projection().inverse(),
+                    inv_projection: camera.get().inverse_projection(),
                     far: match camera.get() {
                         Camera::Perspective { clip, .. } => clip.1,
                         _ => unreachable!(),
@@ -1324,7 +1289,6 @@ fn main() {
 
                 executor.add(Task {
                     resources: [
-                        Buffer(&mersenne_buffer, BufferAccess::ComputeShaderReadWrite),
                         Image(&noise_image, ImageAccess::ComputeShaderReadWrite),
                     ],
                     task: |commands| {
@@ -1333,7 +1297,7 @@ fn main() {
 
                             commands.push_constant(PushConstant {
                                 data: BuildNoisePush {
-                                    mersenne_buffer: (mersenne_buffer)(),
+                                    seed: NOISE_SEED,
                                     noise_image: (noise_image)(),
                                 },
                                 pipeline: &noise_pipeline,
@@ -1997,10 +1961,12 @@ fn main() {
                             .unwrap();
 
                         for i in 0..len as usize {
-                            let sound = unsafe { mem::transmute::<_, Sound>(sounds[i]) };
+                            let Some(sound) = block::sound(sounds[i]) else {
+                                continue;
+                            };
 
                             let mut sound_path = sound_asset_path.clone();
-                            sound_path.push(sound.name());
+                            sound_path.push(sound);
 
                             let file = std::fs::File::open(sound_path).unwrap();
 
@@ -2186,7 +2152,7 @@ pub struct BuildMeshPush {
 #[derive(Clone, Copy)]
 #[repr(C)]
 pub struct BuildNoisePush {
-    pub mersenne_buffer: Buffer,
+    pub seed: u32,
     pub noise_image: Image,
 }
 #[derive(Clone, Copy)]
@@ -0,0 +1,207 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::{env, fs, path};
+
+//generates the block tables and shader defines from assets/blocks.toml
+//only the subset of toml used by that file is understood
+
+struct Block {
+    id: u32,
+    name: String,
+    transparent: bool,
+    solid: bool,
+    from: [f32; 3],
+    to: [f32; 3],
+    noise: f32,
+    sound: Option<(String, u32)>,
+}
+
+fn main() {
+    let manifest_path = path::PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let blocks_path = manifest_path.join("../../assets/blocks.toml");
+
+    println!("cargo:rerun-if-changed={}", blocks_path.display());
+
+    let source = fs::read_to_string(&blocks_path).expect("failed to read blocks.toml");
+
+    let blocks = parse(&source);
+
+    let out_path = path::PathBuf::from(env::var("OUT_DIR").unwrap());
+
+    fs::write(out_path.join("blocks.rs"), generate(&blocks)).expect("failed to write blocks.rs");
+}
+
+fn parse(source: &str) -> Vec<Block> {
+    let mut tables = vec![];
+
+    for (number, line) in source.lines().enumerate() {
+        let line = line.split('#').next().unwrap().trim();
+
+        if line.is_empty() {
+            continue;
+        }
+
+        if line == "[[block]]" {
+            tables.push(HashMap::new());
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once('=') else {
+            panic!("blocks.toml:{}: expected `key = value`", number + 1);
+        };
+
+        let Some(table) = tables.last_mut() else {
+            panic!("blocks.toml:{}: key outside of [[block]]", number + 1);
+        };
+
+        table.insert(key.trim().to_owned(), value.trim().to_owned());
+    }
+
+    let blocks = tables.iter().map(block).collect::<Vec<_>>();
+
+    for (i, block) in blocks.iter().enumerate() {
+        if block.id as usize != i {
+            panic!(
+                "block `{}` has id {}, ids must be contiguous from 0",
+                block.name, block.id
+            );
+        }
+    }
+
+    blocks
+}
+
+fn block(table: &HashMap<String, String>) -> Block {
+    let get = |key: &str| table.get(key).map(String::as_str);
+
+    let required =
+        |key: &str| get(key).unwrap_or_else(|| panic!("block is missing required key `{}`", key));
+
+    let string = |value: &str| {
+        value
+            .strip_prefix('"')
+            .and_then(|value| value.strip_suffix('"'))
+            .unwrap_or_else(|| panic!("expected string, found `{}`", value))
+            .to_owned()
+    };
+
+    let boolean = |value: &str| {
+        value
+            .parse::<bool>()
+            .unwrap_or_else(|_| panic!("expected bool, found `{}`", value))
+    };
+
+    let color = |value: Option<&str>| {
+        let Some(value) = value else {
+            return [1.0; 3];
+        };
+
+        let channels = value
+            .strip_prefix('[')
+            .and_then(|value| value.strip_suffix(']'))
+            .unwrap_or_else(|| panic!("expected color array, found `{}`", value))
+            .split(',')
+            .map(|channel| channel.trim().parse::<u8>().expect("invalid color channel"))
+            .map(|channel| channel as f32 / 256.0)
+            .collect::<Vec<_>>();
+
+        channels
+            .try_into()
+            .unwrap_or_else(|_| panic!("expected 3 color channels, found `{}`", value))
+    };
+
+    let name = string(required("name"));
+
+    let sound = get("sound").map(|sound| {
+        let count = required("sound_count")
+            .parse::<u32>()
+            .expect("invalid sound count");
+
+        (string(sound), count)
+    });
+
+    Block {
+        id: required("id").parse().expect("invalid block id"),
+        transparent: boolean(required("transparent")),
+        solid: boolean(required("solid")),
+        from: color(get("from")),
+        to: color(get("to")),
+        noise: get("noise").map_or(1.0, |noise| noise.parse().expect("invalid noise scale")),
+        sound,
+        name,
+    }
+}
+
+fn generate(blocks: &[Block]) -> String {
+    let mut rust = String::new();
+    let mut defines = vec![];
+
+    //sound ids start at 1 so 0 can mean no sound
+    let mut sounds = vec![];
+    let mut sound_ranges = vec![];
+
+    for block in blocks {
+        let start = sounds.len() as u32 + 1;
+
+        if let Some((sound, count)) = &block.sound {
+            for n in 1..=*count {
+                sounds.push(format!("{}_{}.ogg", sound, n));
+            }
+        }
+
+        sound_ranges.push(start..sounds.len() as u32 + 1);
+    }
+
+    let vec3 = |color: [f32; 3]| format!("vec3({:?}, {:?}, {:?})", color[0], color[1], color[2]);
+
+    let table = |name: &str, ty: &str, values: Vec<String>| {
+        format!("{} {}[]({})", name, ty, values.join(", "))
+    };
+
+    writeln!(rust, "impl Id {{").unwrap();
+    for block in blocks {
+        let name = block.name.to_uppercase();
+
+        writeln!(rust, "    pub const {}: Self = Self({});", name, block.id).unwrap();
+
+        defines.push(format!("BLOCK_ID_{} {}", name, block.id));
+    }
+    writeln!(rust, "}}").unwrap();
+
+    writeln!(rust, "pub const BLOCKS: &[Block] = &[").unwrap();
+    for (block, sounds) in blocks.iter().zip(&sound_ranges) {
+        writeln!(
+            rust,
+            "    Block {{ id: Id({}), name: {:?}, transparent: {}, solid: {}, from: {:?}, to: {:?}, noise: {:?}, sounds: {:?} }},",
+            block.id,
+            block.name,
+            block.transparent,
+            block.solid,
+            block.from,
+            block.to,
+            block.noise,
+            sounds,
+        )
+        .unwrap();
+    }
+    writeln!(rust, "];").unwrap();
+
+    writeln!(rust, "pub const SOUNDS: &[&str] = &{:?};", sounds).unwrap();
+
+    let solid = blocks.iter().map(|block| block.solid.to_string());
+    let from = blocks.iter().map(|block| vec3(block.from));
+    let to = blocks.iter().map(|block| vec3(block.to));
+    let sound = sound_ranges.iter().map(|sounds| sounds.start.to_string());
+    let count = sound_ranges.iter().map(|sounds| sounds.len().to_string());
+
+    defines.push(format!("BLOCK_COUNT {}", blocks.len()));
+    defines.push(table("BLOCK_SOLID_TABLE", "bool", solid.collect()));
+    defines.push(table("BLOCK_FROM_TABLE", "vec3", from.collect()));
+    defines.push(table("BLOCK_TO_TABLE", "vec3", to.collect()));
+    defines.push(table("BLOCK_SOUND_TABLE", "uint", sound.collect()));
+    defines.push(table("BLOCK_SOUND_COUNT_TABLE", "uint", count.collect()));
+
+    writeln!(rust, "pub const DEFINES: &[&str] = &{:?};", defines).unwrap();
+
+    rust
+}
@@ -1,5 +1,14 @@
-const U32_bits: usize = 32;
+use std::cmp;
+use std::ops;
 
+const U32_BITS: usize = 32;
+
+#[derive(Debug)]
+pub enum Error {
+    OutOfBounds,
+}
+
+#[derive(Clone, Debug)]
 pub struct Bitset {
     len: usize,
     data: Vec<u32>,
@@ -12,6 +21,15 @@ impl Bitset {
             data: vec![],
         }
     }
+
+    //reserves room for the bits without growing the set
+    pub fn with_capacity(bits: usize) -> Self {
+        Self {
+            len: 0,
+            data: Vec::with_capacity(bits.div_ceil(U32_BITS)),
+        }
+    }
+
     pub fn len(&self) -> usize {
         self.len
     }
@@ -20,28 +38,150 @@ impl Bitset {
         &self.data
     }
 
-    pub fn insert(&mut self, index: usize, value: bool) -> Result<bool, ()> {
-        while self.len <= index {
+    fn grow(&mut self, len: usize) {
+        while self.len < len {
             self.data.push(0);
-            self.len = self.data.len() * U32_bits;
+            self.len = self.data.len() * U32_BITS;
         }
+    }
+
+    pub fn insert(&mut self, index: usize, value: bool) -> Result<bool, Error> {
+        self.grow(index + 1);
 
         let previous = self.get(index)?;
 
         if value {
-            self.data[index / U32_bits] |= 1 << index % U32_bits;
+            self.data[index / U32_BITS] |= 1 << index % U32_BITS;
         } else {
-            self.data[index / U32_bits] &= !(1 << index % U32_bits);
+            self.data[index / U32_BITS] &= !(1 << index % U32_BITS);
         }
 
         Ok(previous)
     }
 
-    pub fn get(&self, index: usize) -> Result<bool, ()> {
+    pub fn get(&self, index: usize) -> Result<bool, Error> {
         if self.len <= index {
-            return Err(());
+            return Err(Error::OutOfBounds);
+        }
+
+        Ok(self.data[index / U32_BITS] & (1 << index % U32_BITS) != 0)
+    }
+
+    pub fn fill(&mut self, range: ops::Range<usize>, value: bool) {
+        if range.is_empty() {
+            return;
+        }
+
+        self.grow(range.end);
+
+        let first = range.start / U32_BITS;
+        let last = (range.end - 1) / U32_BITS;
+
+        for word in first..=last {
+            let start = if word == first {
+                range.start % U32_BITS
+            } else {
+                0
+            };
+
+            let end = if word == last {
+                (range.end - 1) % U32_BITS + 1
+            } else {
+                U32_BITS
+            };
+
+            let mask = (u32::MAX >> (U32_BITS - (end - start))) << start;
+
+            if value {
+                self.data[word] |= mask;
+            } else {
+                self.data[word] &= !mask;
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.data.fill(0);
+    }
+
+    pub fn count_ones(&self) -> usize {
+        self.data
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    pub fn iter(&self) -> Ones<'_> {
+        Ones {
+            data: &self.data,
+            word: 0,
+            bits: self.data.first().copied().unwrap_or(0),
         }
+    }
+
+    pub fn union(&mut self, other: &Self) {
+        self.grow(other.len);
+
+        for (word, other) in self.data.iter_mut().zip(&other.data) {
+            *word |= other;
+        }
+    }
+
+    pub fn intersection(&mut self, other: &Self) {
+        for (i, word) in self.data.iter_mut().enumerate() {
+            *word &= other.data.get(i).copied().unwrap_or(0);
+        }
+    }
+
+    pub fn difference(&mut self, other: &Self) {
+        for (word, other) in self.data.iter_mut().zip(&other.data) {
+            *word &= !other;
+        }
+    }
+}
+
+//sets are equal when the same bits are set, however far either has grown
+impl PartialEq for Bitset {
+    fn eq(&self, other: &Self) -> bool {
+        let len = cmp::max(self.data.len(), other.data.len());
+
+        (0..len).all(|i| {
+            self.data.get(i).copied().unwrap_or(0) == other.data.get(i).copied().unwrap_or(0)
+        })
+    }
+}
+
+impl Eq for Bitset {}
+
+pub struct Ones<'a> {
+    data: &'a [u32],
+    word: usize,
+    bits: u32,
+}
+
+impl Iterator for Ones<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.bits == 0 {
+            self.word += 1;
+            self.bits = *self.data.get(self.word)?;
+        }
+
+        let bit = self.bits.trailing_zeros() as usize;
+
+        //clear the lowest set bit
+        self.bits &= self.bits - 1;
+
+        Some(self.word * U32_BITS + bit)
+    }
+}
+
+impl<'a> IntoIterator for &'a Bitset {
+    type Item = usize;
+    type IntoIter = Ones<'a>;
 
-        Ok(self.data[index / U32_bits] & (1 << index % U32_bits) != 0)
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
     }
 }
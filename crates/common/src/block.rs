@@ -0,0 +1,45 @@
+use std::ops;
+
+//tables are generated by build.rs from assets/blocks.toml
+include!(concat!(env!("OUT_DIR"), "/blocks.rs"));
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct Id(u32);
+
+impl Id {
+    pub fn from_u32(id: u32) -> Option<Self> {
+        ((id as usize) < BLOCKS.len()).then_some(Self(id))
+    }
+
+    pub fn as_u32(self) -> u32 {
+        self.0
+    }
+
+    pub fn block(self) -> &'static Block {
+        &BLOCKS[self.0 as usize]
+    }
+}
+
+#[derive(Debug)]
+pub struct Block {
+    pub id: Id,
+    pub name: &'static str,
+    pub transparent: bool,
+    pub solid: bool,
+    pub from: [f32; 3],
+    pub to: [f32; 3],
+    pub noise: f32,
+    //ids into SOUNDS offset by one, matching the shader
+    pub sounds: ops::Range<u32>,
+}
+
+impl Block {
+    pub fn by_name(name: &str) -> Option<&'static Block> {
+        BLOCKS.iter().find(|block| block.name == name)
+    }
+}
+
+pub fn sound(id: u32) -> Option<&'static str> {
+    SOUNDS.get(id.checked_sub(1)? as usize).copied()
+}
@@ -1,7 +1,9 @@
 #![feature(default_free_fn)]
 
 pub mod bits;
+pub mod block;
 pub mod convert;
 pub mod mesh;
+pub mod net;
 pub mod octree;
 pub mod voxel;
@@ -1,15 +1,113 @@
-use serde::{Serialize, Deserialize};
-use math::{Vector};
+use math::prelude::*;
 
-const ACK_COUNT: usize = 64;
+use ::net::{Channel, Deserializable, Serializable};
 
-#[derive(Serialize, Deserialize)]
-pub struct Packet {
-    ack: [u64; ACK_COUNT],
-    message: Message,
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use std::time::Duration;
+
+//bumped whenever the packets or messages change, mismatched clients are denied
+pub const PROTOCOL_VERSION: u32 = 2;
+
+//either side sends a heartbeat when it has been quiet this long
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_millis(250);
+//and drops the other after hearing nothing for this long
+pub const TIMEOUT: Duration = Duration::from_secs(5);
+
+pub type Connection = ::net::Connection<Message>;
+
+//every datagram between client and server
+//the client sends a request, echoes the challenge it gets back and is then accepted with its
+//entity id, so a spoofed address never gets a session
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Packet {
+    Request { version: u32 },
+    Challenge { token: u64 },
+    Response { token: u64 },
+    Accept { id: usize },
+    Deny { reason: Deny },
+    //bytes for the session's connection
+    Payload(Vec<u8>),
+    Heartbeat,
+    Disconnect,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Deny {
+    VersionMismatch,
+    ServerFull,
+}
+
+//little endian: a tag byte, then the fields in order, ids as u64
+impl Serializable for Packet {
+    fn serialize(&self, buffer: &mut Vec<u8>) {
+        match self {
+            Packet::Request { version } => {
+                buffer.push(0);
+                buffer.extend_from_slice(&version.to_le_bytes());
+            }
+            Packet::Challenge { token } => {
+                buffer.push(1);
+                buffer.extend_from_slice(&token.to_le_bytes());
+            }
+            Packet::Response { token } => {
+                buffer.push(2);
+                buffer.extend_from_slice(&token.to_le_bytes());
+            }
+            Packet::Accept { id } => {
+                buffer.push(3);
+                buffer.extend_from_slice(&(*id as u64).to_le_bytes());
+            }
+            Packet::Deny { reason } => {
+                buffer.push(4);
+                buffer.push(*reason as u8);
+            }
+            Packet::Payload(bytes) => {
+                buffer.push(5);
+                buffer.extend_from_slice(bytes);
+            }
+            Packet::Heartbeat => buffer.push(6),
+            Packet::Disconnect => buffer.push(7),
+        }
+    }
+}
+
+impl Deserializable for Packet {
+    fn deserialize(bytes: &[u8]) -> Option<Self> {
+        let (&tag, bytes) = bytes.split_first()?;
+
+        let u32 = || Some(u32::from_le_bytes(bytes.try_into().ok()?));
+        let u64 = || Some(u64::from_le_bytes(bytes.try_into().ok()?));
+
+        match tag {
+            0 => Some(Packet::Request { version: u32()? }),
+            1 => Some(Packet::Challenge { token: u64()? }),
+            2 => Some(Packet::Response { token: u64()? }),
+            3 => Some(Packet::Accept {
+                id: u64()? as usize,
+            }),
+            4 => {
+                let reason = match bytes {
+                    [0] => Deny::VersionMismatch,
+                    [1] => Deny::ServerFull,
+                    _ => None?,
+                };
+
+                Some(Packet::Deny { reason })
+            }
+            5 => Some(Packet::Payload(bytes.to_vec())),
+            6 => bytes.is_empty().then_some(Packet::Heartbeat),
+            7 => bytes.is_empty().then_some(Packet::Disconnect),
+            _ => None,
+        }
+    }
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Message {
     None,
     Spawn {
@@ -19,6 +117,115 @@ pub enum Message {
     Move {
         id: usize,
         position: Vector<f32, 3>,
+    },
+    Despawn {
+        id: usize,
+    },
+    //what the client's player wants to do, the server decides what happens
+    //direction is horizontal in world space, x and z, and no longer than one
+    Input {
+        direction: Vector<f32, 2>,
+        jump: bool,
+    },
+}
+
+impl Message {
+    //a lost move or input is replaced by the next one, a lost spawn would leave the entity missing
+    pub fn channel(&self) -> Channel {
+        match self {
+            Message::Spawn { .. } | Message::Despawn { .. } => Channel::ReliableOrdered,
+            Message::None | Message::Move { .. } | Message::Input { .. } => Channel::Unreliable,
+        }
     }
 }
 
+//little endian: a tag byte, then the fields in order, ids as u64 and vectors as f32s
+impl Serializable for Message {
+    fn serialize(&self, buffer: &mut Vec<u8>) {
+        match *self {
+            Message::None => buffer.push(0),
+            Message::Spawn { id, position } => {
+                buffer.push(1);
+                buffer.extend_from_slice(&(id as u64).to_le_bytes());
+                write_vector(buffer, position);
+            }
+            Message::Move { id, position } => {
+                buffer.push(2);
+                buffer.extend_from_slice(&(id as u64).to_le_bytes());
+                write_vector(buffer, position);
+            }
+            Message::Despawn { id } => {
+                buffer.push(3);
+                buffer.extend_from_slice(&(id as u64).to_le_bytes());
+            }
+            Message::Input { direction, jump } => {
+                buffer.push(4);
+                write_vector(buffer, direction);
+                buffer.push(jump as u8);
+            }
+        }
+    }
+}
+
+impl Deserializable for Message {
+    fn deserialize(bytes: &[u8]) -> Option<Self> {
+        let (&tag, mut bytes) = bytes.split_first()?;
+
+        let bytes = &mut bytes;
+
+        let message = match tag {
+            0 => Message::None,
+            1 => Message::Spawn {
+                id: read_id(bytes)?,
+                position: read_vector(bytes)?,
+            },
+            2 => Message::Move {
+                id: read_id(bytes)?,
+                position: read_vector(bytes)?,
+            },
+            3 => Message::Despawn {
+                id: read_id(bytes)?,
+            },
+            4 => Message::Input {
+                direction: read_vector(bytes)?,
+                jump: match read::<1>(bytes)? {
+                    [0] => false,
+                    [1] => true,
+                    _ => None?,
+                },
+            },
+            _ => None?,
+        };
+
+        bytes.is_empty().then_some(message)
+    }
+}
+
+fn write_vector<const N: usize>(buffer: &mut Vec<u8>, vector: Vector<f32, N>) {
+    for x in vector.iter() {
+        buffer.extend_from_slice(&x.to_le_bytes());
+    }
+}
+
+//takes N bytes off the front
+fn read<const N: usize>(bytes: &mut &[u8]) -> Option<[u8; N]> {
+    let (head, tail) = bytes.split_first_chunk::<N>()?;
+
+    *bytes = tail;
+
+    Some(*head)
+}
+
+fn read_id(bytes: &mut &[u8]) -> Option<usize> {
+    Some(u64::from_le_bytes(read(bytes)?) as usize)
+}
+
+fn read_vector<const N: usize>(bytes: &mut &[u8]) -> Option<Vector<f32, N>> {
+    let mut vector = Vector::new([0.0; N]);
+
+    for i in 0..N {
+        vector[i] = f32::from_le_bytes(read(bytes)?);
+    }
+
+    Some(vector)
+}
@@ -3,70 +3,150 @@ use crate::convert::*;
 use crate::mesh::*;
 use crate::voxel::*;
 
+use math::morton;
 use math::prelude::*;
 
 use std::cmp;
+use std::collections::VecDeque;
 use std::default::default;
+use std::io;
 use std::iter;
 use std::marker;
 
-//TODO change this with dynamic size
-const SIZE: usize = 10;
+const DEFAULT_DEPTH: usize = 10;
+
+//morton codes carry a 3 bit sentinel followed by 3 bits per level
+const MAX_DEPTH: usize = 20;
+
+const FORMAT_MAGIC: [u8; 4] = *b"HXOT";
+const FORMAT_VERSION: u32 = 1;
+//nodes reserved up front when loading, more are grown into as they are read
+const MAX_PRELOAD_NODES: usize = 1 << 16;
 
 #[derive(Debug)]
 pub enum Error {
     NodeNotFound,
     NoData,
     InvalidMask,
+    InvalidDepth,
+    OutOfBounds,
+    Io(io::Error),
+    InvalidMagic,
+    UnsupportedVersion(u32),
+    InvalidVoxel(u32),
+    CorruptNode(usize),
+}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Coverage {
+    Empty,
+    Partial,
+    Full,
 }
 
 pub trait Octree<T> {
     fn new() -> Self;
 
-    fn place(&mut self, position: Vector<usize, 3>, object: T);
+    fn place(&mut self, position: Vector<usize, 3>, object: T) -> Result<(), Error>;
+    fn remove(&mut self, position: Vector<usize, 3>) -> Result<Option<T>, Error>;
     fn query(&self, position: Vector<usize, 3>) -> Option<&T>;
 }
 
+#[derive(Clone)]
 pub struct SparseOctree<T: Eq + Copy + Default> {
-    size: usize,
+    depth: usize,
     nodes: Vec<Node<T>>,
     data: marker::PhantomData<[T]>,
 }
 
 impl<T: Eq + Copy + Default> Octree<T> for SparseOctree<T> {
     fn new() -> Self {
-        let size = SIZE;
+        Self::with_depth(DEFAULT_DEPTH).expect("default depth is valid")
+    }
 
-        let mut nodes = vec![];
+    fn place(&mut self, position: Vector<usize, 3>, data: T) -> Result<(), Error> {
+        let [x, y, z] = <[usize; 3]>::from(*position);
 
-        nodes.push(Node {
-            morton: 0,
-            child: u32::MAX,
-            valid: 0,
-            data: default(),
-        });
+        let hierarchy = self.get_position_hierarchy(x, y, z)?;
 
-        Self {
-            size,
-            nodes,
-            data: marker::PhantomData,
-        }
+        let node = self.add_node(&hierarchy)?;
+
+        node.data = Some(data);
+
+        Ok(())
     }
 
-    fn place(&mut self, position: Vector<usize, 3>, data: T) {
+    fn remove(&mut self, position: Vector<usize, 3>) -> Result<Option<T>, Error> {
         let [x, y, z] = <[usize; 3]>::from(*position);
 
-        let hierarchy = self.get_position_hierarchy(x, y, z);
+        let hierarchy = self.get_position_hierarchy(x, y, z)?;
+
+        //descendants without a node of their own fall back to the data of their
+        //deepest ancestor, so any ancestor holding data is split before removing
+        for level in 0..hierarchy.len() {
+            let path = self.get_node_path(&hierarchy[..level])?;
+
+            let index = path[level];
+
+            let mask = hierarchy[level] as u32;
+
+            if self.nodes[index].data.is_some() {
+                self.split_node(index, &hierarchy[..level]);
+            } else if self.nodes[index].valid & mask != mask {
+                return Ok(None);
+            }
+        }
+
+        let mut path = self.get_node_path(&hierarchy)?;
+
+        let leaf = path[hierarchy.len()];
+
+        let data = self.nodes[leaf].data.take();
+
+        //prune every node left without data or children, except the root
+        while path.len() > 1 {
+            let index = path.pop().unwrap();
+
+            let node = self.nodes[index];
+
+            if node.data.is_some() || node.valid != 0 {
+                break;
+            }
+
+            let parent = path[path.len() - 1];
+
+            let mask = hierarchy[path.len() - 1] as u32;
+
+            self.nodes[parent].valid &= !mask;
+
+            if self.nodes[parent].valid == 0 {
+                self.nodes[parent].child = u32::MAX;
+            }
+
+            self.remove_node(index);
 
-        let node = self.add_node(&hierarchy);
+            for ancestor in &mut path {
+                if *ancestor > index {
+                    *ancestor -= 1;
+                }
+            }
+        }
 
-        node.unwrap().data = Some(data);
+        Ok(data)
     }
 
     fn query(&self, position: Vector<usize, 3>) -> Option<&T> {
         let [x, y, z] = <[usize; 3]>::from(*position);
 
-        let hierarchy = self.get_position_hierarchy(x, y, z);
+        let Ok(hierarchy) = self.get_position_hierarchy(x, y, z) else {
+            None?
+        };
 
         let Ok((node, _)) = self.get_node(&hierarchy) else {
             None?
@@ -81,6 +161,25 @@ impl<T: Eq + Copy + Default> Octree<T> for SparseOctree<T> {
 }
 
 impl<T: Eq + Copy + Default> SparseOctree<T> {
+    pub fn with_depth(depth: usize) -> Result<Self, Error> {
+        if depth == 0 || depth > MAX_DEPTH {
+            Err(Error::InvalidDepth)?
+        }
+
+        let nodes = vec![Node {
+            morton: 0,
+            child: u32::MAX,
+            valid: 0,
+            data: default(),
+        }];
+
+        Ok(Self {
+            depth,
+            nodes,
+            data: marker::PhantomData,
+        })
+    }
+
     fn add_node(&mut self, hierarchy: &[u8]) -> Result<&'_ mut Node<T>, Error> {
         let mut index = 0;
 
@@ -136,6 +235,119 @@ impl<T: Eq + Copy + Default> SparseOctree<T> {
         Ok(node)
     }
 
+    //same walk as get_node_path without building the hierarchy, returns the deepest
+    //node found above the given level together with the level it sits at
+    fn get_node_index(&self, position: [usize; 3], level: usize) -> (usize, usize) {
+        let mut index = 0;
+
+        let mut cursor = self.extent();
+
+        for reached in 0..level {
+            cursor /= 2;
+
+            let octant = [0, 1, 2]
+                .map(|i| (position[i] & cursor != 0) as u32)
+                .iter()
+                .fold(0, |octant, &bit| octant << 1 | bit);
+
+            let mask = 1u32 << octant;
+
+            let node = &self.nodes[index];
+
+            if node.valid & mask != mask || node.child == u32::MAX {
+                return (index, reached);
+            }
+
+            index = node.child as usize + (node.valid & (mask - 1)).count_ones() as usize;
+        }
+
+        (index, level)
+    }
+
+    //a node's own data or, for a mixed subtree, that of its first occupied descendant
+    fn sample_node(&self, index: usize) -> Option<T> {
+        let node = &self.nodes[index];
+
+        if node.data.is_some() || node.child == u32::MAX {
+            return node.data;
+        }
+
+        (0..node.valid.count_ones() as usize)
+            .find_map(|child_offset| self.sample_node(node.child as usize + child_offset))
+    }
+
+    //gives every missing child of the node its data and clears the node itself
+    fn split_node(&mut self, mut index: usize, hierarchy: &[u8]) {
+        let Some(data) = self.nodes[index].data else {
+            return;
+        };
+
+        let mut child_hierarchy = hierarchy.to_vec();
+
+        child_hierarchy.push(0);
+
+        for octant in 0..8 {
+            let mask = 1u32 << octant;
+
+            if self.nodes[index].valid & mask == mask {
+                continue;
+            }
+
+            *child_hierarchy.last_mut().unwrap() = mask as u8;
+
+            let morton = Self::get_morton_code(&child_hierarchy);
+
+            let child_offset = (self.nodes[index].valid & (mask - 1)).count_ones() as usize;
+
+            let position = if self.nodes[index].child == u32::MAX {
+                self.nodes.partition_point(|node| node.morton < morton)
+            } else {
+                self.nodes[index].child as usize + child_offset
+            };
+
+            self.insert_node(
+                position,
+                Node {
+                    morton,
+                    data: Some(data),
+                    ..default()
+                },
+            );
+
+            if index >= position {
+                index += 1;
+            }
+
+            self.nodes[index].child = (position - child_offset) as u32;
+            self.nodes[index].valid |= mask;
+        }
+
+        self.nodes[index].data = None;
+    }
+
+    //shifts child indices so the layout stays intact around the new node
+    fn insert_node(&mut self, position: usize, node: Node<T>) {
+        for other in &mut self.nodes {
+            if other.child != u32::MAX && other.child as usize >= position {
+                other.child += 1;
+            }
+        }
+
+        self.nodes.insert(position, node);
+    }
+
+    fn remove_node(&mut self, position: usize) -> Node<T> {
+        let node = self.nodes.remove(position);
+
+        for other in &mut self.nodes {
+            if other.child != u32::MAX && other.child as usize > position {
+                other.child -= 1;
+            }
+        }
+
+        node
+    }
+
     pub fn get_morton_code(hierarchy: &[u8]) -> u64 {
         const MASK: u64 = 0x7;
 
@@ -152,6 +364,273 @@ impl<T: Eq + Copy + Default> SparseOctree<T> {
         morton
     }
 
+    pub fn get_morton_position(&self, morton: u64) -> (Vector<usize, 3>, usize) {
+        //the root is the only code without the leading sentinel
+        if morton == 0 {
+            return (Vector::new([0, 0, 0]), 0);
+        }
+
+        let level = (64 - morton.leading_zeros() as usize - 3) / 3;
+
+        //below the sentinel the octants are the interleaved position
+        let position = morton::decode_3d(morton & ((1 << (3 * level)) - 1));
+
+        let position = Vector::new(position.map(|x| x as usize));
+
+        (position * (self.extent() >> level), level)
+    }
+
+    pub fn fill(&mut self, region: Region, data: T) -> Result<(), Error> {
+        let Region { start, end } = region;
+
+        self.fill_box(start, end, data)
+    }
+
+    pub fn fill_box(
+        &mut self,
+        start: Vector<usize, 3>,
+        end: Vector<usize, 3>,
+        data: T,
+    ) -> Result<(), Error> {
+        let extent = self.extent();
+
+        if (0..3).any(|i| start[i] > end[i] || end[i] > extent) {
+            Err(Error::OutOfBounds)?
+        }
+
+        self.fill_with(data, |position, size| {
+            let mut coverage = Coverage::Full;
+
+            for i in 0..3 {
+                if position[i] + size <= start[i] || position[i] >= end[i] {
+                    return Coverage::Empty;
+                }
+
+                if position[i] < start[i] || position[i] + size > end[i] {
+                    coverage = Coverage::Partial;
+                }
+            }
+
+            coverage
+        });
+
+        Ok(())
+    }
+
+    pub fn fill_sphere(&mut self, center: Vector<f32, 3>, radius: f32, data: T) {
+        let radius_squared = radius * radius;
+
+        //a voxel is inside when its center is, so only voxel centers are measured
+        self.fill_with(data, |position, size| {
+            let mut nearest = 0.0;
+            let mut farthest = 0.0;
+
+            for i in 0..3 {
+                let low = position[i] as f32 + 0.5 - center[i];
+                let high = (position[i] + size) as f32 - 0.5 - center[i];
+
+                let near = if low > 0.0 {
+                    low
+                } else if high < 0.0 {
+                    -high
+                } else {
+                    (low - low.round()).abs()
+                };
+
+                let far = f32::max(low.abs(), high.abs());
+
+                nearest += near * near;
+                farthest += far * far;
+            }
+
+            if nearest > radius_squared {
+                Coverage::Empty
+            } else if farthest <= radius_squared {
+                Coverage::Full
+            } else {
+                Coverage::Partial
+            }
+        })
+    }
+
+    //rebuilds the tree breadth first so families are emitted in morton order,
+    //replacing fully covered subtrees with a single node along the way
+    fn fill_with(&mut self, data: T, coverage: impl Fn(Vector<usize, 3>, usize) -> Coverage) {
+        struct Entry<T> {
+            old: Option<usize>,
+            inherited: Option<T>,
+            position: Vector<usize, 3>,
+            level: usize,
+            morton: u64,
+            coverage: Coverage,
+        }
+
+        let extent = self.extent();
+
+        let mut queue = VecDeque::new();
+
+        queue.push_back(Entry {
+            old: Some(0),
+            inherited: None,
+            position: Vector::new([0, 0, 0]),
+            level: 0,
+            morton: 0,
+            coverage: coverage(Vector::new([0, 0, 0]), extent),
+        });
+
+        let mut enqueued = 1;
+
+        let mut nodes = vec![];
+
+        while let Some(entry) = queue.pop_front() {
+            let old = entry.old.map(|index| self.nodes[index]);
+
+            let mut node = Node {
+                morton: entry.morton,
+                ..default()
+            };
+
+            match entry.coverage {
+                Coverage::Full => {
+                    node.data = Some(data);
+                }
+                Coverage::Empty => {
+                    let Some(old) = old else {
+                        node.data = entry.inherited;
+                        nodes.push(node);
+                        continue;
+                    };
+
+                    node.data = old.data;
+
+                    if old.child != u32::MAX {
+                        node.valid = old.valid;
+                        node.child = enqueued as u32;
+
+                        //untouched subtrees are copied as is and never classified again
+                        for child_offset in 0..old.valid.count_ones() as usize {
+                            let child = old.child as usize + child_offset;
+
+                            queue.push_back(Entry {
+                                old: Some(child),
+                                inherited: None,
+                                position: entry.position,
+                                level: entry.level + 1,
+                                morton: self.nodes[child].morton,
+                                coverage: Coverage::Empty,
+                            });
+
+                            enqueued += 1;
+                        }
+                    }
+                }
+                Coverage::Partial => {
+                    let inherited = match old {
+                        Some(old) => old.data,
+                        None => entry.inherited,
+                    };
+
+                    let size = extent >> (entry.level + 1);
+
+                    let prefix = if entry.level == 0 { 0x7 } else { entry.morton };
+
+                    node.child = enqueued as u32;
+
+                    for octant in 0..8 {
+                        let mask = 1u32 << octant;
+
+                        let position = entry.position
+                            + Vector::new([
+                                (octant >> 2 & 1) * size,
+                                (octant >> 1 & 1) * size,
+                                (octant & 1) * size,
+                            ]);
+
+                        let child = old
+                            .filter(|old| old.valid & mask == mask && old.child != u32::MAX)
+                            .map(|old| {
+                                old.child as usize + (old.valid & (mask - 1)).count_ones() as usize
+                            });
+
+                        let coverage = coverage(position, size);
+
+                        if child.is_none() && inherited.is_none() && coverage == Coverage::Empty {
+                            continue;
+                        }
+
+                        node.valid |= mask;
+
+                        queue.push_back(Entry {
+                            old: child,
+                            inherited,
+                            position,
+                            level: entry.level + 1,
+                            morton: prefix << 3 | octant as u64,
+                            coverage,
+                        });
+
+                        enqueued += 1;
+                    }
+
+                    if node.valid == 0 {
+                        node.child = u32::MAX;
+                    }
+                }
+            }
+
+            nodes.push(node);
+        }
+
+        //children always follow their parent, so walking backwards collapses bottom up
+        for i in (0..nodes.len()).rev() {
+            if nodes[i].valid != u8::MAX as u32 || nodes[i].child == u32::MAX {
+                continue;
+            }
+
+            let child_index = nodes[i].child as usize;
+
+            let child_order = child_index..child_index + 8;
+
+            let all_children_same = nodes[child_order.clone()].iter().all(|node| {
+                node.valid == 0 && node.data.is_some() && node.data == nodes[child_index].data
+            });
+
+            if all_children_same {
+                nodes[i] = Node {
+                    morton: nodes[i].morton,
+                    data: nodes[child_index].data,
+                    ..default()
+                };
+
+                for j in child_order {
+                    nodes[j].morton = u64::MAX;
+                }
+            }
+        }
+
+        let mut remap = Vec::with_capacity(nodes.len());
+
+        let mut retained = 0;
+
+        for node in &nodes {
+            remap.push(retained);
+
+            if node.morton != u64::MAX {
+                retained += 1;
+            }
+        }
+
+        nodes.retain(|node| node.morton != u64::MAX);
+
+        for node in &mut nodes {
+            if node.child != u32::MAX {
+                node.child = remap[node.child as usize];
+            }
+        }
+
+        self.nodes = nodes;
+    }
+
     pub fn optimize(&mut self) {
         let mut nodes = self.nodes.clone();
 
@@ -169,9 +648,11 @@ impl<T: Eq + Copy + Default> SparseOctree<T> {
 
             let child_order = child_index..child_index + 8;
 
-            let all_children_same = nodes[child_order.clone()]
-                .iter()
-                .all(|node| node.data.is_some() && node.data == nodes[child_index].data);
+            let all_children_same = nodes[child_order.clone()].iter().all(|node| {
+                node.child == u32::MAX
+                    && node.data.is_some()
+                    && node.data == nodes[child_index].data
+            });
 
             if all_children_same {
                 nodes[i] = Node {
@@ -212,16 +693,31 @@ impl<T: Eq + Copy + Default> SparseOctree<T> {
         self.nodes = nodes;
     }
 
-    pub fn size(&self) -> usize {
-        self.size
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    pub fn extent(&self) -> usize {
+        1 << self.depth
     }
 
-    pub fn get_position_hierarchy(&self, mut x: usize, mut y: usize, mut z: usize) -> Vec<u8> {
+    pub fn get_position_hierarchy(
+        &self,
+        mut x: usize,
+        mut y: usize,
+        mut z: usize,
+    ) -> Result<Vec<u8>, Error> {
+        let extent = self.extent();
+
+        if x >= extent || y >= extent || z >= extent {
+            Err(Error::OutOfBounds)?
+        }
+
         let mut hierarchy = vec![];
 
-        let mut cursor = 2usize.pow(self.size as _);
+        let mut cursor = extent;
 
-        for i in 0..self.size {
+        for _ in 0..self.depth {
             cursor /= 2;
 
             let px = (x >= cursor) as u8;
@@ -239,41 +735,189 @@ impl<T: Eq + Copy + Default> SparseOctree<T> {
             hierarchy.push(mask);
         }
 
-        hierarchy
+        Ok(hierarchy)
     }
 
     pub fn nodes(&self) -> &'_ [Node<T>] {
         &self.nodes
     }
 
-    pub fn has_data(&self, hierarchy: &[u8]) -> bool {
-        let Ok((node, _)) = self.get_node(&hierarchy) else {
-            return false;
-        };
+    pub fn iter(&self) -> Iter<'_, T> {
+        let extent = self.extent();
 
-        return node.data.is_some();
+        self.iter_region(Region {
+            start: Vector::new([0, 0, 0]),
+            end: Vector::new([extent, extent, extent]),
+        })
     }
 
-    pub fn get_node(&self, hierarchy: &[u8]) -> Result<(&'_ Node<T>, usize), Error> {
-        let mut index = 0;
+    //yields every node overlapping the region, collapsed nodes are not clipped to it
+    pub fn iter_region(&self, region: Region) -> Iter<'_, T> {
+        Iter {
+            octree: self,
+            region,
+            stack: vec![Visit::Node(0)],
+        }
+    }
 
-        for (level, &mask) in hierarchy.iter().enumerate() {
-            if mask.count_ones() != 1 {
-                Err(Error::InvalidMask)?
+    //mirrors the dda in rtx.glsl, except that empty subtrees are crossed in one step
+    pub fn raycast(
+        &self,
+        origin: Vector<f32, 3>,
+        direction: Vector<f32, 3>,
+        max_distance: f32,
+    ) -> Option<RayHit<'_, T>> {
+        let sign = |x: f32| {
+            if x > 0.0 {
+                1.0
+            } else if x < 0.0 {
+                -1.0
+            } else {
+                0.0
             }
+        };
 
-            let child_offset = (self.nodes[index].valid & (mask as u32 - 1)).count_ones();
-
-            let mask_valid = self.nodes[index].valid & mask as u32 == mask as u32;
-            let child_exists = self.nodes[index].child != u32::MAX;
+        let direction = direction.normalize();
 
-            if mask_valid && child_exists {
-                let child_index = self.nodes[index].child as usize;
+        //a zero or broken direction never steps anywhere
+        if !direction.iter().all(|x| x.is_finite()) {
+            None?
+        }
 
-                index = child_index + child_offset as usize;
-            } else {
-                break;
-            }
+        let extent = self.extent() as i64;
+
+        let mut map_pos = [0i64; 3];
+        let mut ray_step = [0i64; 3];
+        let mut delta_dist = [0.0f32; 3];
+        let mut side_dist = [0.0f32; 3];
+
+        for i in 0..3 {
+            map_pos[i] = origin[i].floor() as i64;
+            ray_step[i] = sign(direction[i]) as i64;
+            delta_dist[i] = 1.0 / direction[i].abs();
+            side_dist[i] = (sign(direction[i]) * (map_pos[i] as f32 - origin[i])
+                + sign(direction[i]) * 0.5
+                + 0.5)
+                * delta_dist[i];
+        }
+
+        let mut mask = [false; 3];
+        let mut dist = 0.0;
+
+        loop {
+            if dist > max_distance {
+                None?
+            }
+
+            if map_pos.iter().any(|&x| x < 0 || x >= extent) {
+                None?
+            }
+
+            let position = Vector::new(map_pos.map(|x| x as usize));
+
+            let hierarchy = self
+                .get_position_hierarchy(position[0], position[1], position[2])
+                .ok()?;
+
+            let path = self.get_node_path(&hierarchy).ok()?;
+
+            let node = &self.nodes[path[path.len() - 1]];
+
+            if let Some(data) = &node.data {
+                let destination = origin + direction * dist;
+
+                let normal =
+                    Vector::new([0, 1, 2].map(|i| mask[i] as i32 * sign(-direction[i]) as i32));
+
+                return Some(RayHit {
+                    position,
+                    normal,
+                    destination,
+                    distance: dist,
+                    data,
+                });
+            }
+
+            //the empty region is the missing child below the deepest node found
+            let size = (extent >> cmp::min(path.len(), self.depth)) as i64;
+
+            let mut steps = [0i64; 3];
+            let mut exit_dist = [f32::INFINITY; 3];
+
+            for i in 0..3 {
+                if ray_step[i] == 0 {
+                    continue;
+                }
+
+                let start = map_pos[i] - map_pos[i] % size;
+
+                let last = if ray_step[i] > 0 {
+                    start + size - 1
+                } else {
+                    start
+                };
+
+                steps[i] = (last - map_pos[i]) * ray_step[i];
+                exit_dist[i] = side_dist[i] + steps[i] as f32 * delta_dist[i];
+            }
+
+            let exit = exit_dist.iter().copied().fold(f32::INFINITY, f32::min);
+
+            for i in 0..3 {
+                let crossings = if exit_dist[i] == exit {
+                    steps[i] + 1
+                } else if side_dist[i] <= exit {
+                    let crossings = ((exit - side_dist[i]) / delta_dist[i]).floor() as i64 + 1;
+
+                    cmp::min(crossings, steps[i])
+                } else {
+                    0
+                };
+
+                let last_side = side_dist[i] + (crossings - 1) as f32 * delta_dist[i];
+
+                mask[i] = crossings > 0 && last_side == exit;
+
+                side_dist[i] += crossings as f32 * delta_dist[i];
+                map_pos[i] += crossings * ray_step[i];
+            }
+
+            dist = (0..3)
+                .filter(|&i| mask[i])
+                .map(|i| (side_dist[i] - delta_dist[i]).powi(2))
+                .sum::<f32>()
+                .sqrt();
+        }
+    }
+
+    pub fn has_data(&self, hierarchy: &[u8]) -> bool {
+        let Ok((node, _)) = self.get_node(&hierarchy) else {
+            return false;
+        };
+
+        return node.data.is_some();
+    }
+
+    pub fn get_node(&self, hierarchy: &[u8]) -> Result<(&'_ Node<T>, usize), Error> {
+        let mut index = 0;
+
+        for (level, &mask) in hierarchy.iter().enumerate() {
+            if mask.count_ones() != 1 {
+                Err(Error::InvalidMask)?
+            }
+
+            let child_offset = (self.nodes[index].valid & (mask as u32 - 1)).count_ones();
+
+            let mask_valid = self.nodes[index].valid & mask as u32 == mask as u32;
+            let child_exists = self.nodes[index].child != u32::MAX;
+
+            if mask_valid && child_exists {
+                let child_index = self.nodes[index].child as usize;
+
+                index = child_index + child_offset as usize;
+            } else {
+                break;
+            }
         }
 
         let node = self.get_node_by_index(index).ok_or(Error::NodeNotFound)?;
@@ -281,6 +925,31 @@ impl<T: Eq + Copy + Default> SparseOctree<T> {
         Ok((node, index))
     }
 
+    pub fn get_node_path(&self, hierarchy: &[u8]) -> Result<Vec<usize>, Error> {
+        let mut path = vec![0];
+
+        for &mask in hierarchy {
+            if mask.count_ones() != 1 {
+                Err(Error::InvalidMask)?
+            }
+
+            let index = path[path.len() - 1];
+
+            let child_offset = (self.nodes[index].valid & (mask as u32 - 1)).count_ones();
+
+            let mask_valid = self.nodes[index].valid & mask as u32 == mask as u32;
+            let child_exists = self.nodes[index].child != u32::MAX;
+
+            if !mask_valid || !child_exists {
+                break;
+            }
+
+            path.push(self.nodes[index].child as usize + child_offset as usize);
+        }
+
+        Ok(path)
+    }
+
     pub fn get_node_mut(&mut self, hierarchy: &[u8]) -> Result<(&'_ Node<T>, usize), Error> {
         let (_, index) = self.get_node(&hierarchy)?;
 
@@ -300,6 +969,91 @@ impl<T: Eq + Copy + Default> SparseOctree<T> {
     }
 }
 
+pub struct RayHit<'a, T> {
+    pub position: Vector<usize, 3>,
+    pub normal: Vector<i32, 3>,
+    pub destination: Vector<f32, 3>,
+    pub distance: f32,
+    pub data: &'a T,
+}
+
+enum Visit<'a, T> {
+    Node(usize),
+    //an octant without a node of its own, covered by its parent's data
+    Inherited(u64, &'a T),
+}
+
+pub struct Iter<'a, T: Eq + Copy + Default> {
+    octree: &'a SparseOctree<T>,
+    region: Region,
+    stack: Vec<Visit<'a, T>>,
+}
+
+impl<'a, T: Eq + Copy + Default> Iter<'a, T> {
+    fn overlaps(&self, position: Vector<usize, 3>, level: usize) -> bool {
+        let size = self.octree.extent() >> level;
+
+        (0..3)
+            .all(|i| position[i] < self.region.end[i] && position[i] + size > self.region.start[i])
+    }
+}
+
+impl<'a, T: Eq + Copy + Default> Iterator for Iter<'a, T> {
+    type Item = (Vector<usize, 3>, usize, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(visit) = self.stack.pop() {
+            let index = match visit {
+                Visit::Node(index) => index,
+                Visit::Inherited(morton, data) => {
+                    let (position, level) = self.octree.get_morton_position(morton);
+
+                    if self.overlaps(position, level) {
+                        return Some((position, level, data));
+                    }
+
+                    continue;
+                }
+            };
+
+            let node = &self.octree.nodes[index];
+
+            let (position, level) = self.octree.get_morton_position(node.morton);
+
+            if !self.overlaps(position, level) {
+                continue;
+            }
+
+            if node.child == u32::MAX {
+                if let Some(data) = &node.data {
+                    return Some((position, level, data));
+                }
+
+                continue;
+            }
+
+            let prefix = if level == 0 { 0x7 } else { node.morton };
+
+            //pushed in reverse so children are visited in morton order
+            for octant in (0..8).rev() {
+                let mask = 1u32 << octant;
+
+                if node.valid & mask == mask {
+                    let child_offset = (node.valid & (mask - 1)).count_ones();
+
+                    self.stack
+                        .push(Visit::Node((node.child + child_offset) as usize));
+                } else if let Some(data) = &node.data {
+                    self.stack
+                        .push(Visit::Inherited(prefix << 3 | octant as u64, data));
+                }
+            }
+        }
+
+        None
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 #[repr(C)]
 pub struct Node<T: Eq + Copy + Default> {
@@ -338,6 +1092,172 @@ impl<T: Eq + Copy + Default> Default for Node<T> {
     }
 }
 
+//little endian throughout: a header of magic, version, depth and node count, then
+//the morton sorted nodes, then one voxel id for every node that holds data
+impl SparseOctree<Voxel> {
+    pub fn save(&self, writer: &mut impl io::Write) -> Result<(), Error> {
+        let mut octree = self.clone();
+
+        octree.optimize();
+
+        writer.write_all(&FORMAT_MAGIC)?;
+        writer.write_all(&FORMAT_VERSION.to_le_bytes())?;
+        writer.write_all(&(octree.depth as u32).to_le_bytes())?;
+        writer.write_all(&(octree.nodes.len() as u32).to_le_bytes())?;
+
+        for node in &octree.nodes {
+            writer.write_all(&node.morton.to_le_bytes())?;
+            writer.write_all(&node.child.to_le_bytes())?;
+            writer.write_all(&[node.valid as u8, node.data.is_some() as u8])?;
+        }
+
+        for node in &octree.nodes {
+            if let Some(voxel) = node.data {
+                writer.write_all(&(voxel.id.as_u32()).to_le_bytes())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn load(reader: &mut impl io::Read) -> Result<Self, Error> {
+        let mut magic = [0; 4];
+
+        reader.read_exact(&mut magic)?;
+
+        if magic != FORMAT_MAGIC {
+            Err(Error::InvalidMagic)?
+        }
+
+        let version = read_u32(reader)?;
+
+        if version != FORMAT_VERSION {
+            Err(Error::UnsupportedVersion(version))?
+        }
+
+        let mut octree = Self::with_depth(read_u32(reader)? as usize)?;
+
+        let count = read_u32(reader)? as usize;
+
+        if count == 0 {
+            Err(Error::CorruptNode(0))?
+        }
+
+        //the count is untrusted, a short file fails on read long before this fills up
+        let capacity = cmp::min(count, MAX_PRELOAD_NODES);
+
+        let mut nodes = Vec::with_capacity(capacity);
+        let mut has_data = Vec::with_capacity(capacity);
+
+        for _ in 0..count {
+            let mut morton = [0; 8];
+            let mut flags = [0; 2];
+
+            reader.read_exact(&mut morton)?;
+
+            let child = read_u32(reader)?;
+
+            reader.read_exact(&mut flags)?;
+
+            nodes.push(Node {
+                child,
+                valid: flags[0] as u32,
+                morton: u64::from_le_bytes(morton),
+                data: None,
+            });
+
+            has_data.push(flags[1] != 0);
+        }
+
+        for (node, has_data) in nodes.iter_mut().zip(has_data) {
+            if !has_data {
+                continue;
+            }
+
+            let id = read_u32(reader)?;
+
+            let id = Id::from_u32(id).ok_or(Error::InvalidVoxel(id))?;
+
+            node.data = Some(Voxel { id });
+        }
+
+        octree.validate(&nodes)?;
+
+        octree.nodes = nodes;
+
+        Ok(octree)
+    }
+
+    //checks a loaded node array is sorted and every family matches its parent
+    fn validate(&self, nodes: &[Node<Voxel>]) -> Result<(), Error> {
+        if nodes[0].morton != 0 {
+            Err(Error::CorruptNode(0))?
+        }
+
+        for (i, node) in nodes.iter().enumerate() {
+            if i > 0 && nodes[i - 1].morton >= node.morton {
+                Err(Error::CorruptNode(i))?
+            }
+
+            let Some(level) = morton_level(node.morton).filter(|&level| level <= self.depth) else {
+                Err(Error::CorruptNode(i))?
+            };
+
+            if (node.child == u32::MAX) != (node.valid == 0) || level > self.depth {
+                Err(Error::CorruptNode(i))?
+            }
+
+            if node.child == u32::MAX {
+                continue;
+            }
+
+            let family =
+                node.child as usize..node.child as usize + node.valid.count_ones() as usize;
+
+            if family.end > nodes.len() || level == self.depth {
+                Err(Error::CorruptNode(i))?
+            }
+
+            let prefix = if level == 0 { 0x7 } else { node.morton };
+
+            let octants = (0..8).filter(|octant| node.valid & 1 << octant != 0);
+
+            for (child, octant) in family.zip(octants) {
+                if nodes[child].morton != prefix << 3 | octant as u64 {
+                    Err(Error::CorruptNode(i))?
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+//the level of a stored morton code, none without the sentinel in the right place
+fn morton_level(morton: u64) -> Option<usize> {
+    if morton == 0 {
+        return Some(0);
+    }
+
+    let bits = 64 - morton.leading_zeros() as usize;
+
+    if bits < 6 || !(bits - 3).is_multiple_of(3) {
+        None?
+    }
+
+    let level = (bits - 3) / 3;
+
+    (morton >> (3 * level) == 0x7).then_some(level)
+}
+
+fn read_u32(reader: &mut impl io::Read) -> Result<u32, Error> {
+    let mut bytes = [0; 4];
+
+    reader.read_exact(&mut bytes)?;
+
+    Ok(u32::from_le_bytes(bytes))
+}
+
 impl Convert<Mesh> for SparseOctree<Voxel> {
     fn convert<const N: usize>(&self, conversion: Conversion<N>) -> Mesh {
         let Conversion { regions, lod } = conversion;
@@ -346,141 +1266,227 @@ impl Convert<Mesh> for SparseOctree<Voxel> {
 
         let mut indices = vec![];
 
+        //at a lod every cell is a node that many levels above the leaves
+        let lod = cmp::min(lod, self.depth);
+        let level = self.depth - lod;
+        let scale = 1usize << lod;
+        let cells = (self.extent() >> lod) as isize;
+
+        let sample = |position: Vector<isize, 3>| {
+            if (0..3).any(|i| position[i] < 0 || position[i] >= cells) {
+                None?
+            }
+
+            let position = [0, 1, 2].map(|i| position[i] as usize * scale);
+
+            let (index, reached) = self.get_node_index(position, level);
+
+            if reached < level {
+                self.nodes[index].data
+            } else {
+                self.sample_node(index)
+            }
+        };
+
+        let vertex_ao = |side: Vector<f32, 2>, corner: f32| {
+            (side[0] + side[1] + f32::max(corner, side[0] * side[1])) / 3.0
+        };
+
         for region in regions {
             let Region { start, end } = region;
 
-            let [sx, sy, sz] = *start;
-            let [ex, ey, ez] = *end;
+            let start = [0, 1, 2].map(|i| (start[i] / scale) as isize);
+            let end = [0, 1, 2].map(|i| cmp::min(end[i].div_ceil(scale) as isize, cells));
+
+            if (0..3).any(|i| start[i] >= end[i]) {
+                continue;
+            }
 
-            for x in sx..ex {
-                for y in sy..ey {
-                    for z in sz..ez {
-                        let hierarchy = self.get_position_hierarchy(x, y, z);
+            for axis in 0..3 {
+                //the same axes voxel_ao walks along, so its corners line up with the quad
+                let u = (axis + 2) % 3;
+                let v = (axis + 1) % 3;
 
-                        let Ok((node, index)) = self.get_node(&hierarchy) else {
-                        continue;
-                    };
+                let width = (end[u] - start[u]) as usize;
+                let height = (end[v] - start[v]) as usize;
 
-                        if !self.has_data(&hierarchy) {
-                            continue;
+                //layers carry a one cell border so occlusion can look past the region
+                let layer_width = width + 2;
+                let layer_height = height + 2;
+
+                let sample_layer = |plane: isize| {
+                    let mut layer = Vec::with_capacity(layer_width * layer_height);
+
+                    for j in 0..layer_height {
+                        for i in 0..layer_width {
+                            let mut position = Vector::new([0, 0, 0]);
+
+                            position[axis] = plane;
+                            position[u] = start[u] + i as isize - 1;
+                            position[v] = start[v] + j as isize - 1;
+
+                            layer.push(sample(position));
                         }
+                    }
 
-                        let position = Vector::new([x as f32, y as f32, z as f32, 1.0]);
+                    layer
+                };
+
+                let mut mask = vec![None; width * height];
+
+                let mut front = sample_layer(start[axis] - 1);
 
-                        let color = node.data.unwrap().albedo(position);
+                for plane in start[axis]..=end[axis] {
+                    let back = front;
 
-                        let vertex_ao = |side: Vector<f32, 2>, corner: f32| {
-                            (side[0] + side[1] + f32::max(corner, side[0] * side[1])) / 3.0
+                    front = sample_layer(plane);
+
+                    for direction in [1, -1] {
+                        let (solid, empty, solid_plane) = if direction > 0 {
+                            (&back, &front, plane - 1)
+                        } else {
+                            (&front, &back, plane)
                         };
 
-                        let voxel_ao = |position: Vector<f32, 4>, normal: Vector<f32, 4>| {
-                            let d1 = Vector::new([
-                                isize::abs(normal[1] as isize) as usize,
-                                isize::abs(normal[2] as isize) as usize,
-                                isize::abs(normal[0] as isize) as usize,
-                            ]);
+                        if solid_plane < start[axis] || solid_plane >= end[axis] {
+                            continue;
+                        }
 
-                            let d2 = Vector::new([
-                                isize::abs(normal[2] as isize) as usize,
-                                isize::abs(normal[0] as isize) as usize,
-                                isize::abs(normal[1] as isize) as usize,
-                            ]);
+                        let mut normal = Vector::new([0, 0, 0]);
 
-                            let position = Vector::new([
-                                position[0] as isize + normal[0] as isize,
-                                position[1] as isize + normal[1] as isize,
-                                position[2] as isize + normal[2] as isize,
-                            ]);
+                        normal[axis] = direction;
 
-                            let position = Vector::new([
-                                position[0] as usize,
-                                position[1] as usize,
-                                position[2] as usize,
-                            ]);
+                        //every cell voxel_ao looks at lies in the layer in front of the face
+                        let occupied = |position: Vector<isize, 3>| {
+                            let i = (position[u] - start[u] + 1) as usize;
+                            let j = (position[v] - start[v] + 1) as usize;
+
+                            empty[j * layer_width + i].is_some() as usize as f32
+                        };
+
+                        let voxel_ao = |position: Vector<isize, 3>, normal: Vector<isize, 3>| {
+                            let d1 = normal.abs().swizzle([1, 2, 0]);
+                            let d2 = normal.abs().swizzle([2, 0, 1]);
+
+                            let position = position + normal;
 
                             let side = Vector::new([
-                                self.query(position + d1).is_some() as usize as f32,
-                                self.query(position + d2).is_some() as usize as f32,
-                                self.query(position - d1).is_some() as usize as f32,
-                                self.query(position - d2).is_some() as usize as f32,
+                                occupied(position + d1),
+                                occupied(position + d2),
+                                occupied(position - d1),
+                                occupied(position - d2),
                             ]);
 
                             let corner = Vector::new([
-                                self.query(position + d1 + d2).is_some() as usize as f32,
-                                self.query(position - d1 + d2).is_some() as usize as f32,
-                                self.query(position - d1 - d2).is_some() as usize as f32,
-                                self.query(position + d1 - d2).is_some() as usize as f32,
+                                occupied(position + d1 + d2),
+                                occupied(position - d1 + d2),
+                                occupied(position - d1 - d2),
+                                occupied(position + d1 - d2),
                             ]);
 
                             Vector::new([
-                                1.0 - vertex_ao(Vector::new([side[0], side[1]]), corner[0]),
-                                1.0 - vertex_ao(Vector::new([side[1], side[2]]), corner[1]),
-                                1.0 - vertex_ao(Vector::new([side[2], side[3]]), corner[2]),
-                                1.0 - vertex_ao(Vector::new([side[3], side[0]]), corner[3]),
+                                1.0 - vertex_ao(side.swizzle([0, 1]), corner[0]),
+                                1.0 - vertex_ao(side.swizzle([1, 2]), corner[1]),
+                                1.0 - vertex_ao(side.swizzle([2, 3]), corner[2]),
+                                1.0 - vertex_ao(side.swizzle([3, 0]), corner[3]),
                             ])
                         };
 
-                        let size = 2usize.pow(self.size as _);
+                        for j in 0..height {
+                            for i in 0..width {
+                                let cell = (j + 1) * layer_width + i + 1;
 
-                        let mut normals = vec![];
+                                let mut position = Vector::new([0, 0, 0]);
 
-                        if z < size {
-                            if !self.has_data(&self.get_position_hierarchy(x, y, z + 1)) {
-                                normals.push(Vector::new([0.0, 0.0, 1.0, 0.0]));
-                            }
-                        } else {
-                            normals.push(Vector::new([0.0, 0.0, 1.0, 0.0]));
-                        }
+                                position[axis] = solid_plane;
+                                position[u] = start[u] + i as isize;
+                                position[v] = start[v] + j as isize;
 
-                        if z > 0 {
-                            if !self.has_data(&self.get_position_hierarchy(x, y, z - 1)) {
-                                normals.push(Vector::new([0.0, 0.0, -1.0, 0.0]));
+                                mask[j * width + i] = match solid[cell] {
+                                    Some(voxel) if empty[cell].is_none() => {
+                                        Some((voxel, voxel_ao(position, normal)))
+                                    }
+                                    _ => None,
+                                };
                             }
-                        } else {
-                            normals.push(Vector::new([0.0, 0.0, -1.0, 0.0]));
                         }
 
-                        if x < size {
-                            if !self.has_data(&self.get_position_hierarchy(x + 1, y, z)) {
-                                normals.push(Vector::new([1.0, 0.0, 0.0, 0.0]));
-                            }
-                        } else {
-                            normals.push(Vector::new([1.0, 0.0, 0.0, 0.0]));
-                        }
+                        //merge runs of identical faces into rectangles, row by row
+                        for j in 0..height {
+                            let mut i = 0;
 
-                        if x > 0 {
-                            if !self.has_data(&self.get_position_hierarchy(x - 1, y, z)) {
-                                normals.push(Vector::new([-1.0, 0.0, 0.0, 0.0]));
-                            }
-                        } else {
-                            normals.push(Vector::new([-1.0, 0.0, 0.0, 0.0]));
-                        }
+                            while i < width {
+                                let Some(face) = mask[j * width + i] else {
+                                    i += 1;
+                                    continue;
+                                };
 
-                        if y < size {
-                            if !self.has_data(&self.get_position_hierarchy(x, y + 1, z)) {
-                                normals.push(Vector::new([0.0, 1.0, 0.0, 0.0]));
-                            }
-                        } else {
-                            normals.push(Vector::new([0.0, 1.0, 0.0, 0.0]));
-                        }
+                                let mut w = 1;
 
-                        if y > 0 {
-                            if !self.has_data(&self.get_position_hierarchy(x, y - 1, z)) {
-                                normals.push(Vector::new([0.0, -1.0, 0.0, 0.0]));
-                            }
-                        } else {
-                            normals.push(Vector::new([0.0, -1.0, 0.0, 0.0]));
-                        }
+                                while i + w < width && mask[j * width + i + w] == Some(face) {
+                                    w += 1;
+                                }
 
-                        for normal in normals {
-                            let ambient = voxel_ao(position, normal);
+                                let mut h = 1;
 
-                            vertices.push(Vertex {
-                                position,
-                                color,
-                                normal,
-                                ambient,
-                            });
+                                while j + h < height
+                                    && (0..w).all(|k| mask[(j + h) * width + i + k] == Some(face))
+                                {
+                                    h += 1;
+                                }
+
+                                for row in j..j + h {
+                                    mask[row * width + i..row * width + i + w].fill(None);
+                                }
+
+                                let (voxel, ambient) = face;
+
+                                let mut face_normal = Vector::new([0.0, 0.0, 0.0, 0.0]);
+
+                                face_normal[axis] = direction as f32;
+
+                                let corners = [
+                                    (i, j, ambient[2]),
+                                    (i + w, j, ambient[3]),
+                                    (i + w, j + h, ambient[0]),
+                                    (i, j + h, ambient[1]),
+                                ];
+
+                                let base = vertices.len() as Index;
+
+                                for (corner_u, corner_v, ao) in corners {
+                                    let mut position = Vector::new([0.0, 0.0, 0.0, 1.0]);
+
+                                    position[axis] = (plane as usize * scale) as f32;
+                                    position[u] = ((start[u] as usize + corner_u) * scale) as f32;
+                                    position[v] = ((start[v] as usize + corner_v) * scale) as f32;
+
+                                    vertices.push(Vertex {
+                                        position,
+                                        normal: face_normal,
+                                        color: voxel.albedo(position),
+                                        ambient: Vector::new([ao, ao, ao, ao]),
+                                    });
+                                }
+
+                                //split along the brighter diagonal to keep occlusion from smearing
+                                let triangles =
+                                    if ambient[2] + ambient[0] >= ambient[3] + ambient[1] {
+                                        [[0, 1, 2], [0, 2, 3]]
+                                    } else {
+                                        [[1, 2, 3], [1, 3, 0]]
+                                    };
+
+                                //corners run counter clockwise around the negative axis
+                                for [a, b, c] in triangles {
+                                    let triangle =
+                                        if direction > 0 { [a, c, b] } else { [a, b, c] };
+
+                                    indices.extend(triangle.map(|corner| base + corner));
+                                }
+
+                                i += w;
+                            }
                         }
                     }
                 }
@@ -492,51 +1498,202 @@ impl Convert<Mesh> for SparseOctree<Voxel> {
 }
 
 impl Convert<Bitset> for SparseOctree<Voxel> {
-    //TODO implement this for N regions
+    //bits follow a complete octree laid out level by level, a node's bit is set when
+    //it is entirely covered by data, either its own or that of an ancestor
     fn convert<const N: usize>(&self, conversion: Conversion<N>) -> Bitset {
         let Conversion { regions, lod } = conversion;
 
-        let Region { start, end } = regions[0];
+        let extent = self.extent();
+
+        let max_level = self.depth - cmp::min(lod, self.depth);
 
-        let [sx, sy, sz] = *start;
-        let [ex, ey, ez] = *end;
+        let overlaps = |position: [usize; 3], level: usize| {
+            let size = extent >> level;
+
+            regions.iter().any(|Region { start, end }| {
+                (0..3).all(|i| position[i] < end[i] && position[i] + size > start[i])
+            })
+        };
 
         let mut bitset = Bitset::new();
 
         //root node is a given
-        bitset.insert(0, true);
+        bitset.insert(0, true).unwrap();
+
+        struct Entry {
+            index: Option<usize>,
+            inherited: bool,
+            level: usize,
+            position: [usize; 3],
+            offset: usize,
+        }
 
-        for x in sx..ex {
-            for y in sy..ey {
-                for z in sz..ez {
-                    let hierarchy = self.get_position_hierarchy(x, y, z);
+        let mut stack = vec![Entry {
+            index: Some(0),
+            inherited: false,
+            level: 0,
+            position: [0; 3],
+            offset: 0,
+        }];
 
-                    for level in (0..hierarchy.len()).rev() {
-                        let Ok((node, _)) = self.get_node(&hierarchy[..=level]) else {
-                            break;
-                        };
+        while let Some(entry) = stack.pop() {
+            if !overlaps(entry.position, entry.level) {
+                continue;
+            }
 
-                        if !self.has_data(&hierarchy[..=level]) {
-                            break;
-                        }
+            let node = entry.index.map(|index| &self.nodes[index]);
 
-                        let mut index = 0;
+            let occupied = entry.inherited || node.is_some_and(|node| node.data.is_some());
 
-                        for i in 0..=level {
-                            index += 8usize.pow(i as u32) as usize;
-                        }
+            if entry.level > 0 && occupied {
+                let level_offset = (8usize.pow(entry.level as u32) - 1) / 7;
 
-                        for (i, &mask) in hierarchy[..=level].iter().rev().enumerate() {
-                            let previous = 8usize.pow(i as u32) as usize;
-                            index += mask.trailing_zeros() as usize * previous;
-                        }
+                bitset.insert(level_offset + entry.offset, true).unwrap();
+            }
 
-                        bitset.insert(index, true).unwrap();
-                    }
+            if entry.level == max_level {
+                continue;
+            }
+
+            let size = extent >> (entry.level + 1);
+
+            for octant in 0..8 {
+                let mask = 1u32 << octant;
+
+                let index = node
+                    .filter(|node| node.valid & mask == mask && node.child != u32::MAX)
+                    .map(|node| {
+                        node.child as usize + (node.valid & (mask - 1)).count_ones() as usize
+                    });
+
+                if index.is_none() && !occupied {
+                    continue;
                 }
+
+                let position = [
+                    entry.position[0] + (octant >> 2 & 1) * size,
+                    entry.position[1] + (octant >> 1 & 1) * size,
+                    entry.position[2] + (octant & 1) * size,
+                ];
+
+                stack.push(Entry {
+                    index,
+                    inherited: index.is_none(),
+                    level: entry.level + 1,
+                    position,
+                    offset: entry.offset * 8 + octant,
+                });
             }
         }
 
         bitset
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn single_voxel() -> SparseOctree<u32> {
+        let mut octree = SparseOctree::with_depth(4).unwrap();
+
+        octree.place(Vector::new([8, 8, 8]), 1).unwrap();
+
+        octree
+    }
+
+    #[test]
+    fn raycast_hits_voxel() {
+        let octree = single_voxel();
+
+        let hit = octree
+            .raycast(
+                Vector::new([8.5, 8.5, 0.5]),
+                Vector::new([0.0, 0.0, 1.0]),
+                100.0,
+            )
+            .unwrap();
+
+        assert_eq!(hit.position, Vector::new([8, 8, 8]));
+        assert_eq!(hit.normal, Vector::new([0, 0, -1]));
+        assert_eq!(*hit.data, 1);
+    }
+
+    #[test]
+    fn raycast_rejects_degenerate_direction() {
+        let octree = single_voxel();
+
+        let origin = Vector::new([8.5, 8.5, 0.5]);
+
+        let directions = [
+            Vector::new([0.0, 0.0, 0.0]),
+            Vector::new([f32::NAN, 0.0, 1.0]),
+            Vector::new([0.0, f32::INFINITY, 0.0]),
+            Vector::new([0.0, 0.0, 1e-30]),
+        ];
+
+        for direction in directions {
+            assert!(octree.raycast(origin, direction, 100.0).is_none());
+        }
+    }
+
+    fn header(depth: u32, count: u32) -> Vec<u8> {
+        let mut bytes = FORMAT_MAGIC.to_vec();
+
+        for x in [FORMAT_VERSION, depth, count] {
+            bytes.extend_from_slice(&x.to_le_bytes());
+        }
+
+        bytes
+    }
+
+    fn push_node(bytes: &mut Vec<u8>, morton: u64, child: u32, valid: u8) {
+        bytes.extend_from_slice(&morton.to_le_bytes());
+        bytes.extend_from_slice(&child.to_le_bytes());
+        bytes.extend_from_slice(&[valid, 0]);
+    }
+
+    #[test]
+    fn load_round_trip() {
+        let mut octree = SparseOctree::<Voxel>::with_depth(4).unwrap();
+
+        let voxel = Voxel {
+            id: crate::block::Block::by_name("stone").unwrap().id,
+        };
+
+        octree.place(Vector::new([3, 9, 14]), voxel).unwrap();
+
+        let mut bytes = vec![];
+
+        octree.save(&mut bytes).unwrap();
+
+        let loaded = SparseOctree::<Voxel>::load(&mut &bytes[..]).unwrap();
+
+        assert!(loaded.query(Vector::new([3, 9, 14])) == Some(&voxel));
+        assert!(loaded.query(Vector::new([3, 9, 13])).is_none());
+    }
+
+    #[test]
+    fn load_rejects_huge_count() {
+        let bytes = header(4, u32::MAX);
+
+        let result = SparseOctree::<Voxel>::load(&mut &bytes[..]);
+
+        assert!(matches!(result, Err(Error::Io(_))));
+    }
+
+    #[test]
+    fn load_rejects_bad_morton() {
+        //no sentinel, one below the sentinel and one deeper than the octree
+        for (depth, morton) in [(4, 1), (4, 0x7), (4, 0x7 << 2), (1, 0x7 << 6)] {
+            let mut bytes = header(depth, 2);
+
+            push_node(&mut bytes, 0, u32::MAX, 0);
+            push_node(&mut bytes, morton, u32::MAX, 0);
+
+            let result = SparseOctree::<Voxel>::load(&mut &bytes[..]);
+
+            assert!(matches!(result, Err(Error::CorruptNode(1))));
+        }
+    }
+}
@@ -0,0 +1,413 @@
+use std::fmt;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use crate::Real;
+
+//signed q32.32 fixed point, every operation is plain integer math so results are
+//identical on every machine, which f32 on the gpu can't promise
+//arithmetic wraps on overflow in debug and release alike, so a build can't change a result,
+//and division by zero panics like it does for integers
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct Fixed(i64);
+
+impl Fixed {
+    pub const FRACTION_BITS: u32 = 32;
+
+    pub const ZERO: Self = Self(0);
+    pub const ONE: Self = Self(1 << Self::FRACTION_BITS);
+    pub const MIN: Self = Self(i64::MIN);
+    pub const MAX: Self = Self(i64::MAX);
+    //the smallest step above zero
+    pub const DELTA: Self = Self(1);
+
+    pub const PI: Self = Self(13493037705);
+    pub const TAU: Self = Self(26986075409);
+    pub const FRAC_PI_2: Self = Self(6746518852);
+
+    pub const fn from_bits(bits: i64) -> Self {
+        Self(bits)
+    }
+
+    pub const fn to_bits(self) -> i64 {
+        self.0
+    }
+
+    pub const fn from_int(value: i32) -> Self {
+        Self((value as i64) << Self::FRACTION_BITS)
+    }
+
+    //rounds towards negative infinity
+    pub const fn to_int(self) -> i32 {
+        (self.0 >> Self::FRACTION_BITS) as i32
+    }
+
+    pub fn from_f32(value: f32) -> Self {
+        Self::from_f64(value as f64)
+    }
+
+    pub fn to_f32(self) -> f32 {
+        self.to_f64() as f32
+    }
+
+    //saturates outside of the representable range
+    pub fn from_f64(value: f64) -> Self {
+        Self((value * Self::ONE.0 as f64) as i64)
+    }
+
+    pub fn to_f64(self) -> f64 {
+        self.0 as f64 / Self::ONE.0 as f64
+    }
+
+    //saturates, the absolute value of MIN is MAX
+    pub fn abs(self) -> Self {
+        Self(self.0.saturating_abs())
+    }
+
+    //atan for arguments in -1..=1
+    fn atan(self) -> Self {
+        //two half angle steps, atan(t) = 2 atan(t / (1 + sqrt(1 + t^2))), bring the
+        //argument under tan(pi / 16) where a short series is accurate to the last bit
+        let mut t = self;
+        for _ in 0..2 {
+            t = t / (Self::ONE + (Self::ONE + t * t).sqrt());
+        }
+
+        let t2 = t * t;
+
+        let mut sum = Self(Self::ONE.0 / 13);
+        for n in [11, 9, 7, 5, 3, 1] {
+            sum = Self(Self::ONE.0 / n) - t2 * sum;
+        }
+
+        Self((t * sum).0 << 2)
+    }
+}
+
+impl Real for Fixed {
+    const EPSILON: Self = Self::DELTA;
+    //there is no infinity, the largest value stands in for it
+    const INFINITY: Self = Self::MAX;
+
+    fn sqrt(self) -> Self {
+        assert!(
+            self.0 >= 0,
+            "attempt to take the square root of a negative number"
+        );
+
+        //sqrt(a * 2^32) * 2^16 == sqrt(a) * 2^32
+        Self(((self.0 as u128) << Self::FRACTION_BITS).isqrt() as i64)
+    }
+
+    fn floor(self) -> Self {
+        Self(self.0 & !(Self::ONE.0 - 1))
+    }
+
+    fn ceil(self) -> Self {
+        Self(self.0.wrapping_add(Self::ONE.0 - 1)).floor()
+    }
+
+    fn sin(self) -> Self {
+        //reduce to -pi..pi, then fold onto -pi/2..pi/2 where the series converges quickly
+        let mut x = Self(self.0.rem_euclid(Self::TAU.0));
+
+        if x > Self::PI {
+            x -= Self::TAU;
+        }
+
+        if x > Self::FRAC_PI_2 {
+            x = Self::PI - x;
+        } else if x < -Self::FRAC_PI_2 {
+            x = -Self::PI - x;
+        }
+
+        //horner form of the taylor series up to x^15
+        let x2 = x * x;
+
+        let mut sum = Self::ONE;
+        for n in [210, 156, 110, 72, 42, 20, 6] {
+            sum = Self::ONE - Self((x2 * sum).0 / n);
+        }
+
+        x * sum
+    }
+
+    fn cos(self) -> Self {
+        (Self::FRAC_PI_2 - self).sin()
+    }
+
+    //saturates at and around the poles, where the quotient is out of range
+    fn tan(self) -> Self {
+        let (sin, cos) = (self.sin(), self.cos());
+
+        if cos == Self::ZERO {
+            return if sin < Self::ZERO {
+                Self::MIN
+            } else {
+                Self::MAX
+            };
+        }
+
+        let tan = ((sin.0 as i128) << Self::FRACTION_BITS) / cos.0 as i128;
+
+        Self(tan.clamp(i64::MIN as i128, i64::MAX as i128) as i64)
+    }
+
+    fn asin(self) -> Self {
+        self.atan2((Self::ONE - self * self).sqrt())
+    }
+
+    fn acos(self) -> Self {
+        (Self::ONE - self * self).sqrt().atan2(self)
+    }
+
+    fn atan2(self, other: Self) -> Self {
+        let (y, x) = (self, other);
+
+        if x == Self::ZERO && y == Self::ZERO {
+            return Self::ZERO;
+        }
+
+        if y.abs() <= x.abs() {
+            let angle = (y / x).atan();
+
+            if x > Self::ZERO {
+                angle
+            } else if y >= Self::ZERO {
+                angle + Self::PI
+            } else {
+                angle - Self::PI
+            }
+        } else {
+            let angle = (x / y).atan();
+
+            if y > Self::ZERO {
+                Self::FRAC_PI_2 - angle
+            } else {
+                -Self::FRAC_PI_2 - angle
+            }
+        }
+    }
+}
+
+impl fmt::Debug for Fixed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.to_f64(), f)
+    }
+}
+
+impl fmt::Display for Fixed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.to_f64(), f)
+    }
+}
+
+macro_rules! impl_from {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Fixed {
+                fn from(value: $t) -> Self {
+                    Self::from_int(value as i32)
+                }
+            }
+        )*
+    };
+}
+
+impl_from!(u8, u16, i8, i16, i32);
+
+impl From<Fixed> for f32 {
+    fn from(value: Fixed) -> Self {
+        value.to_f32()
+    }
+}
+
+impl From<Fixed> for f64 {
+    fn from(value: Fixed) -> Self {
+        value.to_f64()
+    }
+}
+
+//wraps when the sum is out of range
+impl Add for Fixed {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self(self.0.wrapping_add(rhs.0))
+    }
+}
+
+impl AddAssign for Fixed {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+//wraps when the difference is out of range
+impl Sub for Fixed {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self(self.0.wrapping_sub(rhs.0))
+    }
+}
+
+impl SubAssign for Fixed {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+//rounds towards negative infinity and wraps when the product is out of range
+impl Mul for Fixed {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self(((self.0 as i128 * rhs.0 as i128) >> Self::FRACTION_BITS) as i64)
+    }
+}
+
+impl MulAssign for Fixed {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+//rounds towards zero and wraps when the quotient is out of range
+impl Div for Fixed {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        Self((((self.0 as i128) << Self::FRACTION_BITS) / rhs.0 as i128) as i64)
+    }
+}
+
+impl DivAssign for Fixed {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs;
+    }
+}
+
+//wraps, the negation of MIN is MIN
+impl Neg for Fixed {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self(self.0.wrapping_neg())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //about 40 steps of DELTA, the series and the half angle steps lose a few bits each
+    const TOLERANCE: f64 = 1e-8;
+
+    fn assert_near(actual: Fixed, expected: f64) {
+        assert!(
+            (actual.to_f64() - expected).abs() <= TOLERANCE,
+            "{} != {}",
+            actual,
+            expected
+        );
+    }
+
+    //-8..8 in uneven steps, so no sample lands on a multiple of pi
+    fn samples() -> impl Iterator<Item = f64> {
+        (-800..=800).map(|i| i as f64 * 0.01 + 0.003)
+    }
+
+    #[test]
+    fn trigonometry() {
+        for x in samples() {
+            let fixed = Fixed::from_f64(x);
+
+            //the argument itself is rounded to a multiple of DELTA
+            let x = fixed.to_f64();
+
+            assert_near(fixed.sin(), x.sin());
+            assert_near(fixed.cos(), x.cos());
+        }
+
+        for i in -100..=100 {
+            let x = i as f64 / 100.0;
+
+            let fixed = Fixed::from_f64(x);
+            let x = fixed.to_f64();
+
+            assert_near(fixed.asin(), x.asin());
+            assert_near(fixed.acos(), x.acos());
+        }
+    }
+
+    #[test]
+    fn atan2() {
+        for y in samples().step_by(40) {
+            for x in samples().step_by(40) {
+                let (fixed_y, fixed_x) = (Fixed::from_f64(y), Fixed::from_f64(x));
+
+                assert_near(
+                    fixed_y.atan2(fixed_x),
+                    fixed_y.to_f64().atan2(fixed_x.to_f64()),
+                );
+            }
+        }
+
+        assert_eq!(Fixed::ZERO.atan2(Fixed::ZERO), Fixed::ZERO);
+    }
+
+    #[test]
+    fn sqrt() {
+        for x in [0.0, 1e-9, 0.25, 1.0, 2.0, 12345.678, 2e9] {
+            let fixed = Fixed::from_f64(x);
+
+            assert_near(fixed.sqrt(), fixed.to_f64().sqrt());
+        }
+
+        assert_eq!(Fixed::from_int(49).sqrt(), Fixed::from_int(7));
+    }
+
+    #[test]
+    fn tan_saturates_at_the_poles() {
+        assert_eq!(Fixed::FRAC_PI_2.tan(), Fixed::MAX);
+        assert_eq!((-Fixed::FRAC_PI_2).tan(), Fixed::MIN);
+        assert_eq!((Fixed::FRAC_PI_2 - Fixed::DELTA).tan(), Fixed::MAX);
+
+        assert_near(
+            Fixed::from_f64(0.5).tan(),
+            Fixed::from_f64(0.5).to_f64().tan(),
+        );
+    }
+
+    #[test]
+    fn overflow_wraps() {
+        assert_eq!(Fixed::MAX + Fixed::DELTA, Fixed::MIN);
+        assert_eq!(Fixed::MIN - Fixed::DELTA, Fixed::MAX);
+        assert_eq!(-Fixed::MIN, Fixed::MIN);
+        assert_eq!(Fixed::MIN.abs(), Fixed::MAX);
+
+        //2^30 * 4 is 2^32, a whole bit past the 31 integer bits
+        assert_eq!(Fixed::from_int(1 << 30) * Fixed::from_int(4), Fixed::ZERO);
+        assert_eq!(Fixed::MAX.ceil(), Fixed::MIN);
+    }
+
+    #[test]
+    fn rounding() {
+        let half = Fixed::from_f64(0.5);
+        let two = Fixed::from_int(2);
+
+        //mul rounds towards negative infinity
+        assert_eq!(Fixed::DELTA * half, Fixed::ZERO);
+        assert_eq!(-Fixed::DELTA * half, -Fixed::DELTA);
+
+        //div rounds towards zero
+        assert_eq!(Fixed::DELTA / two, Fixed::ZERO);
+        assert_eq!(-Fixed::DELTA / two, Fixed::ZERO);
+        assert_eq!(Fixed::from_bits(-3) / two, Fixed::from_bits(-1));
+
+        assert_eq!(Fixed::from_f64(-1.5).floor(), Fixed::from_int(-2));
+        assert_eq!(Fixed::from_f64(-1.5).ceil(), Fixed::from_int(-1));
+        assert_eq!(Fixed::from_f64(-1.5).to_int(), -2);
+    }
+}
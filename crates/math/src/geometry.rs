@@ -0,0 +1,365 @@
+use std::ops::{Add, Div, Sub};
+
+use crate::{matrix::Matrix, vector::Vector, Numeric, Real};
+
+//axis aligned box covering start..=end
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Aabb<T>
+where
+    T: Numeric,
+{
+    pub start: Vector<T, 3>,
+    pub end: Vector<T, 3>,
+}
+
+impl<T> Aabb<T>
+where
+    T: Numeric,
+    T: Add<Output = T>,
+    T: Sub<Output = T>,
+    T: PartialOrd,
+{
+    //corners may be given in any order
+    pub fn new(a: Vector<T, 3>, b: Vector<T, 3>) -> Self {
+        let mut start = a;
+        let mut end = b;
+
+        for i in 0..3 {
+            start[i] = min(a[i], b[i]);
+            end[i] = max(a[i], b[i]);
+        }
+
+        Self { start, end }
+    }
+
+    pub fn from_point(point: Vector<T, 3>) -> Self {
+        Self {
+            start: point,
+            end: point,
+        }
+    }
+
+    pub fn size(&self) -> Vector<T, 3> {
+        self.end - self.start
+    }
+
+    pub fn contains(&self, point: Vector<T, 3>) -> bool {
+        (0..3).all(|i| self.start[i] <= point[i] && point[i] <= self.end[i])
+    }
+
+    pub fn intersects(&self, other: &Self) -> bool {
+        (0..3).all(|i| self.start[i] <= other.end[i] && other.start[i] <= self.end[i])
+    }
+
+    pub fn union(self, other: Self) -> Self {
+        let mut union = self;
+
+        for i in 0..3 {
+            union.start[i] = min(self.start[i], other.start[i]);
+            union.end[i] = max(self.end[i], other.end[i]);
+        }
+
+        union
+    }
+
+    pub fn intersection(self, other: Self) -> Option<Self> {
+        let mut intersection = self;
+
+        for i in 0..3 {
+            intersection.start[i] = max(self.start[i], other.start[i]);
+            intersection.end[i] = min(self.end[i], other.end[i]);
+        }
+
+        (0..3)
+            .all(|i| intersection.start[i] <= intersection.end[i])
+            .then_some(intersection)
+    }
+
+    //grows the box so it contains the point
+    pub fn expand(self, point: Vector<T, 3>) -> Self {
+        self.union(Self::from_point(point))
+    }
+
+    //grows the box by `amount` on every side
+    pub fn inflate(self, amount: T) -> Self {
+        let mut inflated = self;
+
+        for i in 0..3 {
+            inflated.start[i] = self.start[i] - amount;
+            inflated.end[i] = self.end[i] + amount;
+        }
+
+        inflated
+    }
+
+    pub fn closest_point(&self, point: Vector<T, 3>) -> Vector<T, 3> {
+        let mut closest = point;
+
+        for i in 0..3 {
+            closest[i] = max(self.start[i], min(point[i], self.end[i]));
+        }
+
+        closest
+    }
+}
+
+impl<T> Aabb<T>
+where
+    T: Numeric,
+    T: Add<Output = T>,
+    T: Div<Output = T>,
+    T: From<u8>,
+{
+    pub fn center(&self) -> Vector<T, 3> {
+        (self.start + self.end) / T::from(2)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Ray<T>
+where
+    T: Numeric,
+{
+    pub origin: Vector<T, 3>,
+    pub direction: Vector<T, 3>,
+}
+
+impl<T> Ray<T>
+where
+    T: Real,
+{
+    pub fn new(origin: Vector<T, 3>, direction: Vector<T, 3>) -> Self {
+        Self { origin, direction }
+    }
+
+    pub fn at(&self, distance: T) -> Vector<T, 3> {
+        self.origin + self.direction * distance
+    }
+
+    //slab test, returns the entry and exit distance along the ray
+    //the entry is negative when the origin is inside the box
+    pub fn intersect_aabb(&self, aabb: &Aabb<T>) -> Option<(T, T)> {
+        let mut entry = -T::INFINITY;
+        let mut exit = T::INFINITY;
+
+        for i in 0..3 {
+            if self.direction[i] == T::default() {
+                //parallel to the slab, so the origin has to be between its planes
+                if self.origin[i] < aabb.start[i] || self.origin[i] > aabb.end[i] {
+                    None?
+                }
+
+                continue;
+            }
+
+            let inverse = T::from(1) / self.direction[i];
+
+            let a = (aabb.start[i] - self.origin[i]) * inverse;
+            let b = (aabb.end[i] - self.origin[i]) * inverse;
+
+            entry = max(entry, min(a, b));
+            exit = min(exit, max(a, b));
+        }
+
+        (entry <= exit && exit >= T::default()).then_some((entry, exit))
+    }
+
+    pub fn intersect_plane(&self, plane: &Plane<T>) -> Option<T> {
+        let denominator = plane.normal.dot(self.direction);
+
+        if denominator == T::default() {
+            None?
+        }
+
+        let distance = -plane.signed_distance(self.origin) / denominator;
+
+        (distance >= T::default()).then_some(distance)
+    }
+
+    pub fn intersect_sphere(&self, sphere: &Sphere<T>) -> Option<(T, T)> {
+        let offset = self.origin - sphere.center;
+
+        let a = self.direction.dot(self.direction);
+        let b = offset.dot(self.direction);
+        let c = offset.dot(offset) - sphere.radius * sphere.radius;
+
+        let discriminant = b * b - a * c;
+
+        if discriminant < T::default() || a == T::default() {
+            None?
+        }
+
+        let root = discriminant.sqrt();
+
+        let entry = (-b - root) / a;
+        let exit = (-b + root) / a;
+
+        (exit >= T::default()).then_some((entry, exit))
+    }
+}
+
+//points p on the plane satisfy normal.dot(p) + distance == 0
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Plane<T>
+where
+    T: Numeric,
+{
+    pub normal: Vector<T, 3>,
+    pub distance: T,
+}
+
+impl<T> Plane<T>
+where
+    T: Real,
+{
+    pub fn new(normal: Vector<T, 3>, distance: T) -> Self {
+        Self { normal, distance }
+    }
+
+    pub fn from_point_normal(point: Vector<T, 3>, normal: Vector<T, 3>) -> Self {
+        Self {
+            normal,
+            distance: -normal.dot(point),
+        }
+    }
+
+    pub fn normalize(self) -> Self {
+        let magnitude = self.normal.dot(self.normal).sqrt();
+
+        Self {
+            normal: self.normal / magnitude,
+            distance: self.distance / magnitude,
+        }
+    }
+
+    //positive on the side the normal points to, in units of the normal's length
+    pub fn signed_distance(&self, point: Vector<T, 3>) -> T {
+        self.normal.dot(point) + self.distance
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Sphere<T>
+where
+    T: Numeric,
+{
+    pub center: Vector<T, 3>,
+    pub radius: T,
+}
+
+impl<T> Sphere<T>
+where
+    T: Real,
+{
+    pub fn new(center: Vector<T, 3>, radius: T) -> Self {
+        Self { center, radius }
+    }
+
+    pub fn contains(&self, point: Vector<T, 3>) -> bool {
+        let offset = point - self.center;
+        offset.dot(offset) <= self.radius * self.radius
+    }
+
+    pub fn intersects(&self, other: &Self) -> bool {
+        let offset = other.center - self.center;
+        let radius = self.radius + other.radius;
+        offset.dot(offset) <= radius * radius
+    }
+
+    pub fn intersects_aabb(&self, aabb: &Aabb<T>) -> bool {
+        self.contains(aabb.closest_point(self.center))
+    }
+
+    pub fn aabb(&self) -> Aabb<T> {
+        Aabb::from_point(self.center).inflate(self.radius)
+    }
+}
+
+//planes face inwards, ordered left, right, bottom, top, near, far
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Frustum<T>
+where
+    T: Numeric,
+{
+    pub planes: [Plane<T>; 6],
+}
+
+impl<T> Frustum<T>
+where
+    T: Real,
+{
+    //extracts the planes from a view projection matrix, column major with depth in 0..1
+    //for an infinite projection the far plane is degenerate and culls nothing
+    pub fn from_matrix(matrix: Matrix<T, 4, 4>) -> Self {
+        let row = |r: usize| [matrix[0][r], matrix[1][r], matrix[2][r], matrix[3][r]];
+
+        let plane = |a: [T; 4], b: [T; 4], sign: T| {
+            let normal = Vector::new([a[0] + b[0] * sign, a[1] + b[1] * sign, a[2] + b[2] * sign]);
+            let plane = Plane::new(normal, a[3] + b[3] * sign);
+
+            if normal.dot(normal) == T::default() {
+                plane
+            } else {
+                plane.normalize()
+            }
+        };
+
+        let one = T::from(1);
+        let zero = [T::default(); 4];
+
+        Self {
+            planes: [
+                plane(row(3), row(0), one),
+                plane(row(3), row(0), -one),
+                plane(row(3), row(1), one),
+                plane(row(3), row(1), -one),
+                plane(zero, row(2), one),
+                plane(row(3), row(2), -one),
+            ],
+        }
+    }
+
+    pub fn contains(&self, point: Vector<T, 3>) -> bool {
+        self.planes
+            .iter()
+            .all(|plane| plane.signed_distance(point) >= T::default())
+    }
+
+    pub fn intersects_sphere(&self, sphere: &Sphere<T>) -> bool {
+        self.planes
+            .iter()
+            .all(|plane| plane.signed_distance(sphere.center) >= -sphere.radius)
+    }
+
+    //conservative, boxes near the frustum corners may pass without intersecting
+    pub fn intersects_aabb(&self, aabb: &Aabb<T>) -> bool {
+        self.planes.iter().all(|plane| {
+            //the corner furthest along the normal
+            let mut corner = aabb.start;
+
+            for i in 0..3 {
+                if plane.normal[i] >= T::default() {
+                    corner[i] = aabb.end[i];
+                }
+            }
+
+            plane.signed_distance(corner) >= T::default()
+        })
+    }
+}
+
+fn min<T: PartialOrd>(a: T, b: T) -> T {
+    if b < a {
+        b
+    } else {
+        a
+    }
+}
+
+fn max<T: PartialOrd>(a: T, b: T) -> T {
+    if b > a {
+        b
+    } else {
+        a
+    }
+}
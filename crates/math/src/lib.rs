@@ -1,12 +1,34 @@
-#![feature(convert_float_to_int)]
+#![feature(convert_float_to_int, min_specialization, portable_simd)]
+#![cfg_attr(test, feature(test))]
 
-use std::ops::{Add, AddAssign, Div, DivAssign, Mul, Sub, SubAssign};
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, Neg, Sub, SubAssign};
 
+pub mod fixed;
+pub mod geometry;
 pub mod matrix;
+pub mod morton;
+pub mod noise;
+pub mod quaternion;
 pub mod vector;
 
+mod simd;
+
+pub use fixed::Fixed;
+
+#[cfg(feature = "serde")]
+mod serialize;
+
+#[cfg(feature = "bytemuck")]
+mod pod;
+
 pub mod prelude {
-    pub use crate::{matrix::Matrix, vector::Vector};
+    pub use crate::{
+        fixed::Fixed,
+        matrix::Matrix,
+        quaternion::Quaternion,
+        vector::{Axis, Vector},
+        Real,
+    };
 }
 
 pub trait Numeric:
@@ -40,3 +62,76 @@ impl<T> Numeric for T where
         + std::fmt::Debug
 {
 }
+
+//numbers with the square root and trigonometry needed for rotations and projections
+pub trait Real:
+    Numeric
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+    + PartialOrd
+    + From<u8>
+{
+    const EPSILON: Self;
+    const INFINITY: Self;
+
+    fn sqrt(self) -> Self;
+    fn floor(self) -> Self;
+    fn ceil(self) -> Self;
+    fn sin(self) -> Self;
+    fn cos(self) -> Self;
+    fn tan(self) -> Self;
+    fn asin(self) -> Self;
+    fn acos(self) -> Self;
+    fn atan2(self, other: Self) -> Self;
+}
+
+macro_rules! impl_real {
+    ($t:ty) => {
+        impl Real for $t {
+            const EPSILON: Self = <$t>::EPSILON;
+            const INFINITY: Self = <$t>::INFINITY;
+
+            fn sqrt(self) -> Self {
+                <$t>::sqrt(self)
+            }
+
+            fn floor(self) -> Self {
+                <$t>::floor(self)
+            }
+
+            fn ceil(self) -> Self {
+                <$t>::ceil(self)
+            }
+
+            fn sin(self) -> Self {
+                <$t>::sin(self)
+            }
+
+            fn cos(self) -> Self {
+                <$t>::cos(self)
+            }
+
+            fn tan(self) -> Self {
+                <$t>::tan(self)
+            }
+
+            fn asin(self) -> Self {
+                <$t>::asin(self)
+            }
+
+            fn acos(self) -> Self {
+                <$t>::acos(self)
+            }
+
+            fn atan2(self, other: Self) -> Self {
+                <$t>::atan2(self, other)
+            }
+        }
+    };
+}
+
+impl_real!(f32);
+impl_real!(f64);
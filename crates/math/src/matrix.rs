@@ -2,8 +2,9 @@
 
 use std::ops::{Add, AddAssign, Deref, DerefMut, Div, Mul, Sub, SubAssign};
 
-use crate::{vector::Vector, Numeric};
+use crate::{quaternion::Quaternion, vector::Vector, Numeric, Real};
 
+//column major like glsl, M columns of N rows, so `matrix[c][r]` is row r of column c
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(transparent)]
 pub struct Matrix<T, const N: usize, const M: usize>
@@ -71,19 +72,35 @@ impl<T, const N: usize> Matrix<T, N, N>
 where
     T: Numeric,
     T: From<u8>,
+    T: Add<Output = T>,
+    T: Sub<Output = T>,
     T: Div<Output = T>,
     T: Mul<Output = T>,
     T: PartialOrd,
 {
-    pub fn inverse(mut self) -> Self {
+    pub fn inverse(self) -> Self {
+        self.try_inverse()
+            .expect("attempt to invert a singular matrix")
+    }
+
+    pub fn try_inverse(self) -> Option<Self> {
+        Invert::invert(self)
+    }
+
+    pub(crate) fn gauss_jordan(mut self) -> Option<Self> {
         let mut data = *Self::identity();
 
-        //apply guass jordan elimination
+        //apply gauss jordan elimination with partial pivoting
         for i in 0..N {
-            if self.data[i][i] == T::default() {
-                panic!("attempt to divide by zero")
+            let pivot = self.pivot(i);
+
+            if self.data[pivot][i] == T::default() {
+                None?
             }
 
+            self.data.swap(i, pivot);
+            data.swap(i, pivot);
+
             for j in 0..N {
                 if i != j {
                     let ratio = self.data[j][i] / self.data[i][i];
@@ -99,7 +116,7 @@ where
             }
         }
 
-        //row operation ot make principal diagonal element equal to 1
+        //row operation to make principal diagonal element equal to 1
         for i in 0..N {
             let divisor = self.data[i][i];
             for j in 0..N {
@@ -110,7 +127,145 @@ where
             }
         }
 
-        Self { data }
+        Some(Self { data })
+    }
+
+    pub fn determinant(mut self) -> T {
+        let zero = T::default();
+
+        let mut determinant = T::from(1);
+
+        //reduce to upper triangular, the determinant is the product of the diagonal
+        for i in 0..N {
+            let pivot = self.pivot(i);
+
+            if self.data[pivot][i] == zero {
+                return zero;
+            }
+
+            if pivot != i {
+                self.data.swap(i, pivot);
+                determinant = zero - determinant;
+            }
+
+            for j in i + 1..N {
+                let ratio = self.data[j][i] / self.data[i][i];
+                for k in i..N {
+                    let value = self.data[i][k];
+                    self.data[j][k] -= ratio * value;
+                }
+            }
+
+            determinant = determinant * self.data[i][i];
+        }
+
+        determinant
+    }
+
+    //the row at or below `i` with the largest magnitude in column `i`
+    fn pivot(&self, i: usize) -> usize {
+        (i..N).fold(i, |pivot, j| {
+            if abs(self.data[j][i]) > abs(self.data[pivot][i]) {
+                j
+            } else {
+                pivot
+            }
+        })
+    }
+
+    //column major, so an affine transform has a bottom row of 0 0 0 1
+    pub(crate) fn is_affine(&self) -> bool {
+        let zero = T::default();
+
+        self.data[0][3] == zero
+            && self.data[1][3] == zero
+            && self.data[2][3] == zero
+            && self.data[3][3] == T::from(1)
+    }
+
+    //inverts the 3x3 linear part by cofactors and applies it to the negated translation
+    pub(crate) fn try_inverse_affine(self) -> Option<Self> {
+        let m = |r: usize, c: usize| self.data[r][c];
+
+        let cofactor = [
+            [
+                m(1, 1) * m(2, 2) - m(1, 2) * m(2, 1),
+                m(0, 2) * m(2, 1) - m(0, 1) * m(2, 2),
+                m(0, 1) * m(1, 2) - m(0, 2) * m(1, 1),
+            ],
+            [
+                m(1, 2) * m(2, 0) - m(1, 0) * m(2, 2),
+                m(0, 0) * m(2, 2) - m(0, 2) * m(2, 0),
+                m(0, 2) * m(1, 0) - m(0, 0) * m(1, 2),
+            ],
+            [
+                m(1, 0) * m(2, 1) - m(1, 1) * m(2, 0),
+                m(0, 1) * m(2, 0) - m(0, 0) * m(2, 1),
+                m(0, 0) * m(1, 1) - m(0, 1) * m(1, 0),
+            ],
+        ];
+
+        let determinant =
+            m(0, 0) * cofactor[0][0] + m(0, 1) * cofactor[1][0] + m(0, 2) * cofactor[2][0];
+
+        if determinant == T::default() {
+            None?
+        }
+
+        let mut data = *Self::identity();
+
+        for i in 0..3 {
+            for j in 0..3 {
+                data[i][j] = cofactor[i][j] / determinant;
+            }
+        }
+
+        for j in 0..3 {
+            let mut translation = T::default();
+            for i in 0..3 {
+                translation -= data[i][j] * m(3, i);
+            }
+            data[3][j] = translation;
+        }
+
+        Some(Self { data })
+    }
+}
+
+//lets the 4x4 f32 case be specialized with simd
+pub(crate) trait Invert: Sized {
+    fn invert(self) -> Option<Self>;
+}
+
+impl<T, const N: usize> Invert for Matrix<T, N, N>
+where
+    T: Numeric,
+    T: From<u8>,
+    T: Add<Output = T>,
+    T: Sub<Output = T>,
+    T: Div<Output = T>,
+    T: Mul<Output = T>,
+    T: PartialOrd,
+{
+    default fn invert(self) -> Option<Self> {
+        if N == 4 && self.is_affine() {
+            return self.try_inverse_affine();
+        }
+
+        self.gauss_jordan()
+    }
+}
+
+fn abs<T>(value: T) -> T
+where
+    T: Numeric,
+    T: Sub<Output = T>,
+    T: PartialOrd,
+{
+    if value < T::default() {
+        T::default() - value
+    } else {
+        value
     }
 }
 
@@ -195,46 +350,399 @@ where
     }
 }
 
-impl<T, const A: usize, const B: usize, const C: usize> Mul<Matrix<T, C, A>> for Matrix<T, A, B>
+impl<T, const A: usize, const B: usize, const C: usize> Mul<Matrix<T, B, C>> for Matrix<T, A, B>
 where
     T: Numeric,
     T: Add<Output = T>,
     T: Mul<Output = T>,
 {
-    type Output = Matrix<T, C, B>;
+    type Output = Matrix<T, A, C>;
 
-    fn mul(self, rhs: Matrix<T, C, A>) -> Self::Output {
-        let mut data = [Vector::default(); B];
-        for row in 0..B {
-            for col in 0..C {
-                let mut cell = T::default();
-                for i in 0..A {
-                    cell += self.data[row][i] * rhs.data[i][col];
-                }
-                data[row][col] = cell;
-            }
-        }
-        Matrix::<T, C, B>::new(data)
+    default fn mul(self, rhs: Matrix<T, B, C>) -> Self::Output {
+        self.multiply(rhs)
+    }
+}
+
+impl<T, const A: usize, const B: usize> Mul<Vector<T, B>> for Matrix<T, A, B>
+where
+    T: Numeric,
+    T: Add<Output = T>,
+    T: Mul<Output = T>,
+{
+    type Output = Vector<T, A>;
+
+    default fn mul(self, rhs: Vector<T, B>) -> Self::Output {
+        self.transform(rhs)
     }
 }
 
-impl<T, const A: usize, const B: usize> Mul<Vector<T, A>> for Matrix<T, A, B>
+//the generic products, kept callable for checking the simd specializations against
+impl<T, const A: usize, const B: usize> Matrix<T, A, B>
 where
     T: Numeric,
     T: Add<Output = T>,
     T: Mul<Output = T>,
 {
-    type Output = Vector<T, B>;
+    //column c of the product is the columns of self weighted by column c of rhs
+    pub(crate) fn multiply<const C: usize>(self, rhs: Matrix<T, B, C>) -> Matrix<T, A, C> {
+        let mut data = [Vector::default(); C];
+        for col in 0..C {
+            for row in 0..A {
+                let mut cell = T::default();
+                for i in 0..B {
+                    cell += self.data[i][row] * rhs.data[col][i];
+                }
+                data[col][row] = cell;
+            }
+        }
+        Matrix::<T, A, C>::new(data)
+    }
 
-    fn mul(self, rhs: Vector<T, A>) -> Self::Output {
-        let mut data = [T::default(); B];
-        for row in 0..B {
+    pub(crate) fn transform(self, rhs: Vector<T, B>) -> Vector<T, A> {
+        let mut data = [T::default(); A];
+        for row in 0..A {
             let mut cell = T::default();
-            for i in 0..A {
-                cell += self.data[row][i] * rhs[i];
-                data[row] = cell;
+            for i in 0..B {
+                cell += self.data[i][row] * rhs[i];
+            }
+            data[row] = cell;
+        }
+        Vector::<T, A>::new(data)
+    }
+}
+
+//projections target vulkan clip space, y down and depth in 0..1, looking down -z
+//being column major, `matrix[3]` is the translation and `matrix * point` transforms like glsl
+impl<T> Matrix<T, 4, 4>
+where
+    T: Real,
+{
+    //a and b map view depth to clip depth as (a * z + b) / -z
+    fn perspective_from(fov: T, aspect_ratio: T, a: T, b: T) -> Self {
+        let focal_length = T::from(1) / (fov / T::from(2)).tan();
+
+        let mut projection = Self::default();
+
+        projection[0][0] = focal_length / aspect_ratio;
+        projection[1][1] = -focal_length;
+        projection[2][2] = a;
+        projection[2][3] = -T::from(1);
+        projection[3][2] = b;
+
+        projection
+    }
+
+    fn perspective_inverse_from(fov: T, aspect_ratio: T, a: T, b: T) -> Self {
+        let focal_length = T::from(1) / (fov / T::from(2)).tan();
+
+        let mut inverse = Self::default();
+
+        inverse[0][0] = aspect_ratio / focal_length;
+        inverse[1][1] = -T::from(1) / focal_length;
+        inverse[2][3] = T::from(1) / b;
+        inverse[3][2] = -T::from(1);
+        inverse[3][3] = a / b;
+
+        inverse
+    }
+
+    //near maps to depth 0 and far to depth 1
+    pub fn perspective(fov: T, aspect_ratio: T, near: T, far: T) -> Self {
+        let (a, b) = (far / (near - far), near * far / (near - far));
+        Self::perspective_from(fov, aspect_ratio, a, b)
+    }
+
+    pub fn perspective_inverse(fov: T, aspect_ratio: T, near: T, far: T) -> Self {
+        let (a, b) = (far / (near - far), near * far / (near - far));
+        Self::perspective_inverse_from(fov, aspect_ratio, a, b)
+    }
+
+    //near maps to depth 1 and far to depth 0
+    pub fn perspective_reversed(fov: T, aspect_ratio: T, near: T, far: T) -> Self {
+        let (a, b) = (near / (far - near), near * far / (far - near));
+        Self::perspective_from(fov, aspect_ratio, a, b)
+    }
+
+    pub fn perspective_reversed_inverse(fov: T, aspect_ratio: T, near: T, far: T) -> Self {
+        let (a, b) = (near / (far - near), near * far / (far - near));
+        Self::perspective_inverse_from(fov, aspect_ratio, a, b)
+    }
+
+    //near maps to depth 0 and infinity to depth 1
+    pub fn perspective_infinite(fov: T, aspect_ratio: T, near: T) -> Self {
+        Self::perspective_from(fov, aspect_ratio, -T::from(1), -near)
+    }
+
+    pub fn perspective_infinite_inverse(fov: T, aspect_ratio: T, near: T) -> Self {
+        Self::perspective_inverse_from(fov, aspect_ratio, -T::from(1), -near)
+    }
+
+    //near maps to depth 1 and infinity to depth 0
+    pub fn perspective_infinite_reversed(fov: T, aspect_ratio: T, near: T) -> Self {
+        Self::perspective_from(fov, aspect_ratio, T::default(), near)
+    }
+
+    pub fn perspective_infinite_reversed_inverse(fov: T, aspect_ratio: T, near: T) -> Self {
+        Self::perspective_inverse_from(fov, aspect_ratio, T::default(), near)
+    }
+
+    pub fn orthographic(left: T, right: T, top: T, bottom: T, near: T, far: T) -> Self {
+        let two = T::from(2);
+
+        let mut projection = Self::identity();
+
+        projection[0][0] = two / (right - left);
+        projection[1][1] = two / (bottom - top);
+        projection[2][2] = T::from(1) / (near - far);
+        projection[3][0] = -(right + left) / (right - left);
+        projection[3][1] = -(bottom + top) / (bottom - top);
+        projection[3][2] = near / (near - far);
+
+        projection
+    }
+
+    pub fn orthographic_inverse(left: T, right: T, top: T, bottom: T, near: T, far: T) -> Self {
+        let two = T::from(2);
+
+        let mut inverse = Self::identity();
+
+        inverse[0][0] = (right - left) / two;
+        inverse[1][1] = (bottom - top) / two;
+        inverse[2][2] = near - far;
+        inverse[3][0] = (right + left) / two;
+        inverse[3][1] = (bottom + top) / two;
+        inverse[3][2] = -near;
+
+        inverse
+    }
+
+    //view matrix of an eye at `eye` facing `target`
+    pub fn look_at(eye: Vector<T, 3>, target: Vector<T, 3>, up: Vector<T, 3>) -> Self {
+        let (side, up, forward) = basis(eye, target, up);
+
+        let mut view = Self::identity();
+
+        for i in 0..3 {
+            view[i][0] = side[i];
+            view[i][1] = up[i];
+            view[i][2] = -forward[i];
+        }
+
+        view[3][0] = -side.dot(eye);
+        view[3][1] = -up.dot(eye);
+        view[3][2] = forward.dot(eye);
+
+        view
+    }
+
+    pub fn look_at_inverse(eye: Vector<T, 3>, target: Vector<T, 3>, up: Vector<T, 3>) -> Self {
+        let (side, up, forward) = basis(eye, target, up);
+
+        let mut transform = Self::identity();
+
+        for i in 0..3 {
+            transform[0][i] = side[i];
+            transform[1][i] = up[i];
+            transform[2][i] = -forward[i];
+            transform[3][i] = eye[i];
+        }
+
+        transform
+    }
+
+    pub fn translation(translation: Vector<T, 3>) -> Self {
+        let mut matrix = Self::identity();
+
+        for i in 0..3 {
+            matrix[3][i] = translation[i];
+        }
+
+        matrix
+    }
+
+    pub fn scale(scale: Vector<T, 3>) -> Self {
+        let mut matrix = Self::identity();
+
+        for i in 0..3 {
+            matrix[i][i] = scale[i];
+        }
+
+        matrix
+    }
+
+    pub fn rotation(rotation: Quaternion<T>) -> Self {
+        rotation.into()
+    }
+
+    //translation * rotation * scale, so scale is applied first
+    pub fn trs(translation: Vector<T, 3>, rotation: Quaternion<T>, scale: Vector<T, 3>) -> Self {
+        let mut matrix = Self::rotation(rotation);
+
+        for i in 0..3 {
+            matrix[i] *= scale[i];
+            matrix[3][i] = translation[i];
+        }
+
+        matrix
+    }
+
+    pub fn trs_inverse(
+        translation: Vector<T, 3>,
+        rotation: Quaternion<T>,
+        scale: Vector<T, 3>,
+    ) -> Self {
+        let rotation = Self::rotation(rotation);
+
+        let mut matrix = Self::identity();
+
+        //the inverse rotation is the transpose, each row then divided by the scale
+        for c in 0..3 {
+            for r in 0..3 {
+                matrix[c][r] = rotation[r][c] / scale[r];
+            }
+        }
+
+        for r in 0..3 {
+            let mut value = T::default();
+            for c in 0..3 {
+                value -= matrix[c][r] * translation[c];
             }
+            matrix[3][r] = value;
+        }
+
+        matrix
+    }
+}
+
+//orthonormal side, up and forward vectors of an eye facing `target`
+fn basis<T>(
+    eye: Vector<T, 3>,
+    target: Vector<T, 3>,
+    up: Vector<T, 3>,
+) -> (Vector<T, 3>, Vector<T, 3>, Vector<T, 3>)
+where
+    T: Real,
+{
+    let normalize = |vector: Vector<T, 3>| vector / vector.dot(vector).sqrt();
+
+    let forward = normalize(target - eye);
+    let side = normalize(forward.cross(up));
+    let up = side.cross(forward);
+
+    (side, up, forward)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::f32::consts::FRAC_PI_2;
+
+    fn assert_near(actual: Vector<f32, 4>, expected: [f32; 4]) {
+        for i in 0..4 {
+            assert!(
+                (actual[i] - expected[i]).abs() < 1e-5,
+                "{:?} != {:?}",
+                actual,
+                expected
+            );
         }
-        Vector::<T, B>::new(data)
+    }
+
+    fn assert_identity(matrix: Matrix<f32, 4, 4>) {
+        let identity = Matrix::<f32, 4, 4>::identity();
+
+        for c in 0..4 {
+            assert_near(matrix[c], *identity[c]);
+        }
+    }
+
+    #[test]
+    fn perspective_clip_position() {
+        let projection = Matrix::perspective(FRAC_PI_2, 2.0, 1.0, 10.0);
+        let view = Matrix::look_at(
+            Vector::new([0.0, 0.0, 5.0]),
+            Vector::new([0.0, 0.0, 0.0]),
+            Vector::new([0.0, 1.0, 0.0]),
+        );
+
+        //5 in front of the eye, so w is 5 and depth is 10 * (5 - 1) / (5 * (10 - 1))
+        let clip = projection * view * Vector::new([1.0, 2.0, 0.0, 1.0]);
+
+        assert_near(clip, [0.5, -2.0, 40.0 / 9.0, 5.0]);
+    }
+
+    #[test]
+    fn perspective_depth_range() {
+        let projection = Matrix::perspective(FRAC_PI_2, 1.0, 1.0, 10.0);
+        let reversed = Matrix::perspective_reversed(FRAC_PI_2, 1.0, 1.0, 10.0);
+
+        let depth = |projection: Matrix<f32, 4, 4>, z: f32| {
+            let clip = projection * Vector::new([0.0, 0.0, z, 1.0]);
+            clip[2] / clip[3]
+        };
+
+        assert!(depth(projection, -1.0).abs() < 1e-6);
+        assert!((depth(projection, -10.0) - 1.0).abs() < 1e-6);
+        assert!((depth(reversed, -1.0) - 1.0).abs() < 1e-6);
+        assert!(depth(reversed, -10.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn orthographic_clip_position() {
+        let projection = Matrix::orthographic(-2.0, 2.0, 1.0, -1.0, 1.0, 3.0);
+
+        let clip = projection * Vector::new([1.0, 0.5, -2.0, 1.0]);
+
+        assert_near(clip, [0.5, -0.5, 0.5, 1.0]);
+    }
+
+    #[test]
+    fn analytic_inverses() {
+        let (eye, target, up) = (
+            Vector::new([1.0, 2.0, 3.0]),
+            Vector::new([-2.0, 0.5, 1.0]),
+            Vector::new([0.0, 1.0, 0.0]),
+        );
+
+        assert_identity(
+            Matrix::perspective_inverse(1.2, 1.5, 0.1, 100.0)
+                * Matrix::perspective(1.2, 1.5, 0.1, 100.0),
+        );
+        assert_identity(
+            Matrix::orthographic_inverse(-3.0, 1.0, 2.0, -1.0, 0.5, 8.0)
+                * Matrix::orthographic(-3.0, 1.0, 2.0, -1.0, 0.5, 8.0),
+        );
+        assert_identity(
+            Matrix::look_at_inverse(eye, target, up) * Matrix::look_at(eye, target, up),
+        );
+
+        let (translation, rotation, scale) = (
+            Vector::new([4.0, -1.0, 2.0]),
+            Quaternion::from_axis_angle(Vector::new([1.0, 1.0, 0.0]), 0.7),
+            Vector::new([2.0, 0.5, 3.0]),
+        );
+
+        assert_identity(
+            Matrix::trs_inverse(translation, rotation, scale)
+                * Matrix::trs(translation, rotation, scale),
+        );
+    }
+
+    #[test]
+    fn trs_applies_scale_rotation_then_translation() {
+        let rotation = Quaternion::from_axis_angle(Vector::new([0.0, 0.0, 1.0]), FRAC_PI_2);
+
+        let matrix = Matrix::trs(
+            Vector::new([10.0, 0.0, 0.0]),
+            rotation,
+            Vector::new([2.0, 1.0, 1.0]),
+        );
+
+        //scaled to 2 0 0, turned to 0 2 0 and moved to 10 2 0
+        assert_near(
+            matrix * Vector::new([1.0, 0.0, 0.0, 1.0]),
+            [10.0, 2.0, 0.0, 1.0],
+        );
     }
 }
@@ -0,0 +1,426 @@
+use crate::vector::Vector;
+
+//z-order and hilbert curve indices
+//x takes the highest bit of each group, so a 3d code is read the same way as the octree's
+//octants, 4x + 2y + z, three bits per level from the root down
+
+//bits per axis that fit in a u64 code
+pub const BITS_2D: u32 = 32;
+pub const BITS_3D: u32 = 21;
+
+const MASK_2D: [u64; 2] = [0xaaaa_aaaa_aaaa_aaaa, 0x5555_5555_5555_5555];
+const MASK_3D: [u64; 3] = [
+    0x4924_9249_2492_4924,
+    0x2492_4924_9249_2492,
+    0x1249_2492_4924_9249,
+];
+
+#[cfg(all(target_arch = "x86_64", target_feature = "bmi2"))]
+fn deposit(value: u64, mask: u64) -> u64 {
+    unsafe { std::arch::x86_64::_pdep_u64(value, mask) }
+}
+
+#[cfg(all(target_arch = "x86_64", target_feature = "bmi2"))]
+fn extract(value: u64, mask: u64) -> u64 {
+    unsafe { std::arch::x86_64::_pext_u64(value, mask) }
+}
+
+//spreads the low 32 bits so there is a zero between each
+#[cfg(not(all(target_arch = "x86_64", target_feature = "bmi2")))]
+fn split_by_2(value: u64) -> u64 {
+    let mut x = value & 0xffff_ffff;
+    x = (x | x << 16) & 0x0000_ffff_0000_ffff;
+    x = (x | x << 8) & 0x00ff_00ff_00ff_00ff;
+    x = (x | x << 4) & 0x0f0f_0f0f_0f0f_0f0f;
+    x = (x | x << 2) & 0x3333_3333_3333_3333;
+    x = (x | x << 1) & 0x5555_5555_5555_5555;
+    x
+}
+
+#[cfg(not(all(target_arch = "x86_64", target_feature = "bmi2")))]
+fn compact_by_2(value: u64) -> u64 {
+    let mut x = value & 0x5555_5555_5555_5555;
+    x = (x | x >> 1) & 0x3333_3333_3333_3333;
+    x = (x | x >> 2) & 0x0f0f_0f0f_0f0f_0f0f;
+    x = (x | x >> 4) & 0x00ff_00ff_00ff_00ff;
+    x = (x | x >> 8) & 0x0000_ffff_0000_ffff;
+    x = (x | x >> 16) & 0x0000_0000_ffff_ffff;
+    x
+}
+
+//spreads the low 21 bits so there are two zeros between each
+#[cfg(not(all(target_arch = "x86_64", target_feature = "bmi2")))]
+fn split_by_3(value: u64) -> u64 {
+    let mut x = value & 0x1f_ffff;
+    x = (x | x << 32) & 0x001f_0000_0000_ffff;
+    x = (x | x << 16) & 0x001f_0000_ff00_00ff;
+    x = (x | x << 8) & 0x100f_00f0_0f00_f00f;
+    x = (x | x << 4) & 0x10c3_0c30_c30c_30c3;
+    x = (x | x << 2) & 0x1249_2492_4924_9249;
+    x
+}
+
+#[cfg(not(all(target_arch = "x86_64", target_feature = "bmi2")))]
+fn compact_by_3(value: u64) -> u64 {
+    let mut x = value & 0x1249_2492_4924_9249;
+    x = (x | x >> 2) & 0x10c3_0c30_c30c_30c3;
+    x = (x | x >> 4) & 0x100f_00f0_0f00_f00f;
+    x = (x | x >> 8) & 0x001f_0000_ff00_00ff;
+    x = (x | x >> 16) & 0x001f_0000_0000_ffff;
+    x = (x | x >> 32) & 0x0000_0000_001f_ffff;
+    x
+}
+
+#[cfg(not(all(target_arch = "x86_64", target_feature = "bmi2")))]
+fn deposit(value: u64, mask: u64) -> u64 {
+    let shift = mask.trailing_zeros();
+
+    match mask.count_ones() {
+        32 => split_by_2(value) << shift,
+        _ => split_by_3(value) << shift,
+    }
+}
+
+#[cfg(not(all(target_arch = "x86_64", target_feature = "bmi2")))]
+fn extract(value: u64, mask: u64) -> u64 {
+    let shift = mask.trailing_zeros();
+
+    match mask.count_ones() {
+        32 => compact_by_2(value >> shift),
+        _ => compact_by_3(value >> shift),
+    }
+}
+
+pub fn encode_2d(position: Vector<u32, 2>) -> u64 {
+    deposit(position[0] as u64, MASK_2D[0]) | deposit(position[1] as u64, MASK_2D[1])
+}
+
+pub fn decode_2d(code: u64) -> Vector<u32, 2> {
+    Vector::new(MASK_2D.map(|mask| extract(code, mask) as u32))
+}
+
+//only the low 21 bits of each axis are kept
+pub fn encode_3d(position: Vector<u32, 3>) -> u64 {
+    let mut code = 0;
+
+    for i in 0..3 {
+        code |= deposit(position[i] as u64, MASK_3D[i]);
+    }
+
+    code
+}
+
+pub fn decode_3d(code: u64) -> Vector<u32, 3> {
+    Vector::new(MASK_3D.map(|mask| extract(code, mask) as u32))
+}
+
+//component wise sums without decoding, carries are kept inside each axis by filling the
+//other axes' bits with ones, coordinates wrap around at 2^32 in 2d and 2^21 in 3d
+fn add(a: u64, b: u64, masks: &[u64]) -> u64 {
+    masks
+        .iter()
+        .map(|&mask| ((a | !mask).wrapping_add(b & mask)) & mask)
+        .fold(0, |code, axis| code | axis)
+}
+
+fn sub(a: u64, b: u64, masks: &[u64]) -> u64 {
+    masks
+        .iter()
+        .map(|&mask| ((a & mask).wrapping_sub(b & mask)) & mask)
+        .fold(0, |code, axis| code | axis)
+}
+
+pub fn add_2d(a: u64, b: u64) -> u64 {
+    add(a, b, &MASK_2D)
+}
+
+pub fn sub_2d(a: u64, b: u64) -> u64 {
+    sub(a, b, &MASK_2D)
+}
+
+pub fn add_3d(a: u64, b: u64) -> u64 {
+    add(a, b, &MASK_3D)
+}
+
+pub fn sub_3d(a: u64, b: u64) -> u64 {
+    sub(a, b, &MASK_3D)
+}
+
+//the code of the cell at `offset` from `code`, wrapping like add_2d
+pub fn neighbor_2d(code: u64, offset: Vector<i32, 2>) -> u64 {
+    add_2d(code, encode_2d(Vector::new(offset.map(|x| x as u32))))
+}
+
+//the code of the cell at `offset` from `code`, wrapping like add_3d
+pub fn neighbor_3d(code: u64, offset: Vector<i32, 3>) -> u64 {
+    add_3d(code, encode_3d(Vector::new(offset.map(|x| x as u32))))
+}
+
+//skilling's transform between axes and the transposed hilbert index, for `order` bits per axis
+//"programming the hilbert curve", aip conference proceedings 707, 2004
+fn axes_to_transpose<const N: usize>(mut x: [u32; N], order: u32) -> [u32; N] {
+    //inverse undo
+    for bit in (1..order).rev() {
+        let q = 1 << bit;
+        let p = q - 1;
+
+        for i in 0..N {
+            if x[i] & q != 0 {
+                x[0] ^= p;
+            } else {
+                let t = (x[0] ^ x[i]) & p;
+                x[0] ^= t;
+                x[i] ^= t;
+            }
+        }
+    }
+
+    //gray encode
+    for i in 1..N {
+        x[i] ^= x[i - 1];
+    }
+
+    let mut t = 0;
+    for bit in (1..order).rev() {
+        if x[N - 1] & 1 << bit != 0 {
+            t ^= (1 << bit) - 1;
+        }
+    }
+
+    x.map(|x| x ^ t)
+}
+
+fn transpose_to_axes<const N: usize>(mut x: [u32; N], order: u32) -> [u32; N] {
+    //gray decode
+    let t = x[N - 1] >> 1;
+
+    for i in (1..N).rev() {
+        x[i] ^= x[i - 1];
+    }
+
+    x[0] ^= t;
+
+    //undo excess work
+    for bit in 1..order {
+        let q = 1 << bit;
+        let p = q - 1;
+
+        for i in (0..N).rev() {
+            if x[i] & q != 0 {
+                x[0] ^= p;
+            } else {
+                let t = (x[0] ^ x[i]) & p;
+                x[0] ^= t;
+                x[i] ^= t;
+            }
+        }
+    }
+
+    x
+}
+
+//the distance along a hilbert curve filling a 2^order square, adjacent indices are
+//always adjacent cells, which z-order doesn't guarantee
+pub fn hilbert_encode_2d(position: Vector<u32, 2>, order: u32) -> u64 {
+    assert!(order > 0 && order <= BITS_2D, "invalid hilbert order");
+
+    encode_2d(Vector::new(axes_to_transpose(*position, order)))
+}
+
+pub fn hilbert_decode_2d(index: u64, order: u32) -> Vector<u32, 2> {
+    assert!(order > 0 && order <= BITS_2D, "invalid hilbert order");
+
+    Vector::new(transpose_to_axes(*decode_2d(index), order))
+}
+
+pub fn hilbert_encode_3d(position: Vector<u32, 3>, order: u32) -> u64 {
+    assert!(order > 0 && order <= BITS_3D, "invalid hilbert order");
+
+    encode_3d(Vector::new(axes_to_transpose(*position, order)))
+}
+
+pub fn hilbert_decode_3d(index: u64, order: u32) -> Vector<u32, 3> {
+    assert!(order > 0 && order <= BITS_3D, "invalid hilbert order");
+
+    Vector::new(transpose_to_axes(*decode_3d(index), order))
+}
+
+//these only cover the path that was compiled, there is no ci yet, so run them both ways
+//`cargo test -p math morton` and the same with RUSTFLAGS="-C target-feature=+bmi2"
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAX_2D: u32 = u32::MAX;
+    const MAX_3D: u32 = (1 << BITS_3D) - 1;
+
+    fn distance<const N: usize>(a: Vector<u32, N>, b: Vector<u32, N>) -> u32 {
+        (0..N).map(|i| a[i].abs_diff(b[i])).sum()
+    }
+
+    #[test]
+    fn round_trip_2d() {
+        for x in [0, 1, 0x5555_5555, 0xaaaa_aaaa, MAX_2D - 1, MAX_2D] {
+            for y in [0, 1, 0x1234_5678, MAX_2D - 1, MAX_2D] {
+                let position = Vector::new([x, y]);
+
+                assert_eq!(decode_2d(encode_2d(position)), position);
+            }
+        }
+
+        assert_eq!(encode_2d(Vector::new([MAX_2D, MAX_2D])), u64::MAX);
+        assert_eq!(encode_2d(Vector::new([1, 0])), 0b10);
+        assert_eq!(encode_2d(Vector::new([0, 1])), 0b01);
+    }
+
+    #[test]
+    fn round_trip_3d() {
+        for x in [0, 1, 0x0a_5a5a, MAX_3D - 1, MAX_3D] {
+            for y in [0, 1, 0x15_a5a5, MAX_3D] {
+                for z in [0, 0x12_3456, MAX_3D - 1, MAX_3D] {
+                    let position = Vector::new([x, y, z]);
+
+                    assert_eq!(decode_3d(encode_3d(position)), position);
+                }
+            }
+        }
+
+        assert_eq!(encode_3d(Vector::new([MAX_3D; 3])), u64::MAX >> 1);
+        assert_eq!(encode_3d(Vector::new([1, 0, 0])), 0b100);
+        assert_eq!(encode_3d(Vector::new([0, 1, 0])), 0b010);
+        assert_eq!(encode_3d(Vector::new([0, 0, 1])), 0b001);
+
+        //bits past the 21st are dropped
+        assert_eq!(encode_3d(Vector::new([1 << BITS_3D, 0, 0])), 0);
+    }
+
+    #[test]
+    fn add_and_sub_wrap() {
+        let a = Vector::new([MAX_2D - 1, 7]);
+        let b = Vector::new([3, MAX_2D]);
+
+        let sum = decode_2d(add_2d(encode_2d(a), encode_2d(b)));
+        let difference = decode_2d(sub_2d(encode_2d(a), encode_2d(b)));
+
+        assert_eq!(sum, Vector::new([1, 6]));
+        assert_eq!(difference, Vector::new([MAX_2D - 4, 8]));
+
+        let a = Vector::new([MAX_3D, 0, 100]);
+        let b = Vector::new([1, 1, MAX_3D]);
+
+        let sum = decode_3d(add_3d(encode_3d(a), encode_3d(b)));
+        let difference = decode_3d(sub_3d(encode_3d(a), encode_3d(b)));
+
+        assert_eq!(sum, Vector::new([0, 1, 99]));
+        assert_eq!(difference, Vector::new([MAX_3D - 1, MAX_3D, 101]));
+    }
+
+    #[test]
+    fn neighbors_wrap() {
+        let origin = encode_2d(Vector::new([0, 0]));
+
+        assert_eq!(
+            decode_2d(neighbor_2d(origin, Vector::new([-1, 1]))),
+            Vector::new([MAX_2D, 1])
+        );
+        assert_eq!(
+            neighbor_2d(encode_2d(Vector::new([MAX_2D, 5])), Vector::new([1, -1])),
+            encode_2d(Vector::new([0, 4]))
+        );
+
+        let origin = encode_3d(Vector::new([0, 0, 0]));
+
+        assert_eq!(
+            decode_3d(neighbor_3d(origin, Vector::new([-1, 0, 1]))),
+            Vector::new([MAX_3D, 0, 1])
+        );
+        assert_eq!(
+            neighbor_3d(
+                encode_3d(Vector::new([5, MAX_3D, 9])),
+                Vector::new([-2, 1, 0])
+            ),
+            encode_3d(Vector::new([3, 0, 9]))
+        );
+    }
+
+    #[test]
+    fn hilbert_2d() {
+        for order in 1..=5 {
+            let mut previous = hilbert_decode_2d(0, order);
+
+            assert_eq!(previous, Vector::new([0, 0]));
+
+            for index in 1..1u64 << (2 * order) {
+                let position = hilbert_decode_2d(index, order);
+
+                assert!(position.iter().all(|&x| x < 1 << order));
+                assert_eq!(hilbert_encode_2d(position, order), index);
+                assert_eq!(
+                    distance(previous, position),
+                    1,
+                    "order {} index {}",
+                    order,
+                    index
+                );
+
+                previous = position;
+            }
+        }
+    }
+
+    #[test]
+    fn hilbert_3d() {
+        for order in 1..=4 {
+            let mut previous = hilbert_decode_3d(0, order);
+
+            assert_eq!(previous, Vector::new([0, 0, 0]));
+
+            for index in 1..1u64 << (3 * order) {
+                let position = hilbert_decode_3d(index, order);
+
+                assert!(position.iter().all(|&x| x < 1 << order));
+                assert_eq!(hilbert_encode_3d(position, order), index);
+                assert_eq!(
+                    distance(previous, position),
+                    1,
+                    "order {} index {}",
+                    order,
+                    index
+                );
+
+                previous = position;
+            }
+        }
+    }
+
+    #[test]
+    fn hilbert_round_trip_at_full_order() {
+        for position in [
+            [0, 0],
+            [MAX_2D, 0],
+            [MAX_2D, MAX_2D],
+            [0x1234_5678, 0x9abc_def0],
+        ] {
+            let position = Vector::new(position);
+
+            assert_eq!(
+                hilbert_decode_2d(hilbert_encode_2d(position, BITS_2D), BITS_2D),
+                position
+            );
+        }
+
+        for position in [
+            [0, 0, 0],
+            [MAX_3D, 0, MAX_3D],
+            [MAX_3D; 3],
+            [0x12_3456, 7, 0xf_0f0f],
+        ] {
+            let position = Vector::new(position);
+
+            assert_eq!(
+                hilbert_decode_3d(hilbert_encode_3d(position, BITS_3D), BITS_3D),
+                position
+            );
+        }
+    }
+}
@@ -0,0 +1,278 @@
+use std::f32::consts::PI;
+
+use crate::vector::Vector;
+
+//seeded, allocation free gradient and cellular noise
+//the lattice comes from the same integer hash as source/hash.glsl so it is bit exact with
+//the gpu, the float math after that follows build_perlin.glsl and build_worley.glsl step by
+//step and differs only by the precision of the gpu's sin and cos
+
+pub trait Noise {
+    fn sample(&self, position: Vector<f32, 3>) -> f32;
+}
+
+//pcg4d from "hash functions for gpu rendering", jarzynski and olano
+pub fn hash(seed: u32, position: Vector<u32, 3>) -> [u32; 4] {
+    let mut v = [position[0], position[1], position[2], seed]
+        .map(|x| x.wrapping_mul(1664525).wrapping_add(1013904223));
+
+    let mix = |v: &mut [u32; 4]| {
+        v[0] = v[0].wrapping_add(v[1].wrapping_mul(v[3]));
+        v[1] = v[1].wrapping_add(v[2].wrapping_mul(v[0]));
+        v[2] = v[2].wrapping_add(v[0].wrapping_mul(v[1]));
+        v[3] = v[3].wrapping_add(v[1].wrapping_mul(v[2]));
+    };
+
+    mix(&mut v);
+
+    for x in &mut v {
+        *x ^= *x >> 16;
+    }
+
+    mix(&mut v);
+
+    v
+}
+
+//the hash of a lattice cell, wrapped by `period` so the noise tiles like the gpu images
+fn lattice(seed: u32, period: Option<u32>, cell: Vector<i32, 3>) -> [u32; 4] {
+    let wrap = |x: i32| match period {
+        Some(period) => x.rem_euclid(period as i32) as u32,
+        None => x as u32,
+    };
+
+    hash(seed, Vector::new(cell.map(wrap)))
+}
+
+fn unit(x: u32) -> f32 {
+    x as f32 / u32::MAX as f32
+}
+
+//the same as glsl's mix, which is not quite `a + (b - a) * t`
+fn mix(a: f32, b: f32, t: f32) -> f32 {
+    a * (1.0 - t) + b * t
+}
+
+fn cell(position: Vector<f32, 3>) -> Vector<i32, 3> {
+    position.floor().castf::<i32>()
+}
+
+fn to_f32(cell: Vector<i32, 3>) -> Vector<f32, 3> {
+    Vector::new(cell.map(|x| x as f32))
+}
+
+//gradient noise with linear interpolation, roughly in -1..1
+//the gpu stores (sample + 1) / 2 scaled to u32, with 8 texels per lattice cell
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Perlin {
+    pub seed: u32,
+    pub period: Option<u32>,
+}
+
+impl Perlin {
+    pub fn new(seed: u32) -> Self {
+        Self { seed, period: None }
+    }
+
+    //repeats every `period` cells, the gpu noise image is 16 cells wide
+    pub fn tiled(seed: u32, period: u32) -> Self {
+        Self {
+            seed,
+            period: Some(period),
+        }
+    }
+
+    fn gradient(&self, cell: Vector<i32, 3>) -> Vector<f32, 3> {
+        let [x, y, ..] = lattice(self.seed, self.period, cell);
+
+        //the shader's 3.14159265 rounds to the same f32 as PI
+        let alpha = unit(x) * PI;
+        let beta = unit(y) * PI;
+
+        Vector::new([
+            alpha.cos() * beta.cos(),
+            beta.sin(),
+            alpha.sin() * beta.cos(),
+        ])
+    }
+}
+
+impl Noise for Perlin {
+    fn sample(&self, position: Vector<f32, 3>) -> f32 {
+        let m0 = cell(position);
+        let s = position - to_f32(m0);
+
+        let corner = |x, y, z| {
+            let cell = m0 + Vector::new([x, y, z]);
+            self.gradient(cell).dot(position - to_f32(cell))
+        };
+
+        let ix0 = mix(corner(0, 0, 0), corner(1, 0, 0), s[0]);
+        let ix1 = mix(corner(0, 1, 0), corner(1, 1, 0), s[0]);
+        let jx0 = mix(ix0, ix1, s[1]);
+
+        let ix0 = mix(corner(0, 0, 1), corner(1, 0, 1), s[0]);
+        let ix1 = mix(corner(0, 1, 1), corner(1, 1, 1), s[0]);
+        let jx1 = mix(ix0, ix1, s[1]);
+
+        mix(jx0, jx1, s[2])
+    }
+}
+
+//3d simplex noise in -1..1, there is no gpu version yet
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Simplex {
+    pub seed: u32,
+}
+
+impl Simplex {
+    pub fn new(seed: u32) -> Self {
+        Self { seed }
+    }
+}
+
+const SIMPLEX_GRADIENTS: [[f32; 3]; 12] = [
+    [1.0, 1.0, 0.0],
+    [-1.0, 1.0, 0.0],
+    [1.0, -1.0, 0.0],
+    [-1.0, -1.0, 0.0],
+    [1.0, 0.0, 1.0],
+    [-1.0, 0.0, 1.0],
+    [1.0, 0.0, -1.0],
+    [-1.0, 0.0, -1.0],
+    [0.0, 1.0, 1.0],
+    [0.0, -1.0, 1.0],
+    [0.0, 1.0, -1.0],
+    [0.0, -1.0, -1.0],
+];
+
+impl Noise for Simplex {
+    fn sample(&self, position: Vector<f32, 3>) -> f32 {
+        const SKEW: f32 = 1.0 / 3.0;
+        const UNSKEW: f32 = 1.0 / 6.0;
+
+        //find the simplex by skewing space onto a grid of cubes, each split into six tetrahedra
+        let skew = (position[0] + position[1] + position[2]) * SKEW;
+        let base = cell(position + Vector::splat(skew));
+
+        let unskew = (base[0] + base[1] + base[2]) as f32 * UNSKEW;
+        let offset = position - to_f32(base) + Vector::splat(unskew);
+
+        //walk from the base corner towards the far corner along the largest axes first
+        let mut order = [0, 1, 2];
+        order.sort_by(|&a, &b| offset[b].total_cmp(&offset[a]));
+
+        let mut corner = Vector::new([0, 0, 0]);
+        let mut value = 0.0;
+
+        for i in 0..4 {
+            if i > 0 {
+                corner[order[i - 1]] += 1;
+            }
+
+            let offset = offset - to_f32(corner) + Vector::splat(i as f32 * UNSKEW);
+
+            let falloff = 0.6 - offset.dot(offset);
+
+            if falloff <= 0.0 {
+                continue;
+            }
+
+            let [x, ..] = lattice(self.seed, None, base + corner);
+            let gradient = Vector::new(SIMPLEX_GRADIENTS[x as usize % 12]);
+
+            value += falloff.powi(4) * gradient.dot(offset);
+        }
+
+        32.0 * value
+    }
+}
+
+//distance to the nearest feature point, one point per lattice cell
+//the gpu stores this divided by the image width in cells, 16, and scaled to u32
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Worley {
+    pub seed: u32,
+    pub period: Option<u32>,
+}
+
+impl Worley {
+    pub fn new(seed: u32) -> Self {
+        Self { seed, period: None }
+    }
+
+    pub fn tiled(seed: u32, period: u32) -> Self {
+        Self {
+            seed,
+            period: Some(period),
+        }
+    }
+}
+
+impl Noise for Worley {
+    fn sample(&self, position: Vector<f32, 3>) -> f32 {
+        let base = cell(position);
+
+        let mut distance = u32::MAX as f32;
+
+        for x in -1..=1 {
+            for y in -1..=1 {
+                for z in -1..=1 {
+                    let cell = base + Vector::new([x, y, z]);
+
+                    let [a, b, c, _] = lattice(self.seed, self.period, cell);
+                    let point = to_f32(cell) + Vector::new([unit(a), unit(b), unit(c)]);
+
+                    distance = distance.min(point.distance(&position));
+                }
+            }
+        }
+
+        distance
+    }
+}
+
+//fractal brownian motion, sums octaves of another noise at rising frequency and falling amplitude
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Fbm<N>
+where
+    N: Noise,
+{
+    pub noise: N,
+    pub octaves: u32,
+    pub lacunarity: f32,
+    pub gain: f32,
+}
+
+impl<N> Fbm<N>
+where
+    N: Noise,
+{
+    pub fn new(noise: N, octaves: u32) -> Self {
+        Self {
+            noise,
+            octaves,
+            lacunarity: 2.0,
+            gain: 0.5,
+        }
+    }
+}
+
+impl<N> Noise for Fbm<N>
+where
+    N: Noise,
+{
+    fn sample(&self, position: Vector<f32, 3>) -> f32 {
+        let mut value = 0.0;
+        let mut frequency = 1.0;
+        let mut amplitude = 1.0;
+
+        for _ in 0..self.octaves {
+            value += amplitude * self.noise.sample(position * frequency);
+            frequency *= self.lacunarity;
+            amplitude *= self.gain;
+        }
+
+        value
+    }
+}
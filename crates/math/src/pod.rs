@@ -0,0 +1,51 @@
+use bytemuck::{Pod, Zeroable};
+
+use crate::{fixed::Fixed, matrix::Matrix, quaternion::Quaternion, vector::Vector, Numeric};
+
+//all of these are repr(transparent) over arrays of T, so they are pod whenever T is
+
+unsafe impl<T, const N: usize> Zeroable for Vector<T, N>
+where
+    T: Numeric,
+    T: Zeroable,
+{
+}
+
+unsafe impl<T, const N: usize> Pod for Vector<T, N>
+where
+    T: Numeric,
+    T: Pod,
+{
+}
+
+unsafe impl<T, const N: usize, const M: usize> Zeroable for Matrix<T, N, M>
+where
+    T: Numeric,
+    T: Zeroable,
+{
+}
+
+unsafe impl<T, const N: usize, const M: usize> Pod for Matrix<T, N, M>
+where
+    T: Numeric,
+    T: Pod,
+{
+}
+
+unsafe impl<T> Zeroable for Quaternion<T>
+where
+    T: Numeric,
+    T: Zeroable,
+{
+}
+
+unsafe impl<T> Pod for Quaternion<T>
+where
+    T: Numeric,
+    T: Pod,
+{
+}
+
+unsafe impl Zeroable for Fixed {}
+
+unsafe impl Pod for Fixed {}
@@ -0,0 +1,467 @@
+#![allow(clippy::needless_range_loop)]
+
+use std::ops::{Add, Deref, DerefMut, Div, Mul, MulAssign, Sub};
+
+use crate::{matrix::Matrix, vector::Vector, Numeric, Real};
+
+//stored as xyzw to match a glsl vec4
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct Quaternion<T>
+where
+    T: Numeric,
+{
+    data: [T; 4],
+}
+
+impl<T> Quaternion<T>
+where
+    T: Numeric,
+{
+    pub fn new(data: [T; 4]) -> Self {
+        Self { data }
+    }
+
+    pub fn vector(&self) -> Vector<T, 3> {
+        Vector::new([self.data[0], self.data[1], self.data[2]])
+    }
+
+    pub fn scalar(&self) -> T {
+        self.data[3]
+    }
+}
+
+impl<T> Quaternion<T>
+where
+    T: Numeric,
+    T: From<u8>,
+{
+    pub fn identity() -> Self {
+        let mut data = [T::default(); 4];
+        data[3] = 1_u8.into();
+        Self { data }
+    }
+}
+
+impl<T> Quaternion<T>
+where
+    T: Numeric,
+    T: Add<Output = T>,
+    T: Sub<Output = T>,
+    T: Mul<Output = T>,
+{
+    pub fn dot(self, rhs: Self) -> T {
+        let mut dot = T::default();
+        for i in 0..4 {
+            dot += self.data[i] * rhs.data[i];
+        }
+        dot
+    }
+
+    pub fn conjugate(self) -> Self {
+        let [x, y, z, w] = self.data;
+        let zero = T::default();
+        Self {
+            data: [zero - x, zero - y, zero - z, w],
+        }
+    }
+}
+
+impl<T> Default for Quaternion<T>
+where
+    T: Numeric,
+    T: From<u8>,
+{
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl<T> Deref for Quaternion<T>
+where
+    T: Numeric,
+{
+    type Target = [T; 4];
+
+    fn deref(&self) -> &Self::Target {
+        &self.data
+    }
+}
+
+impl<T> DerefMut for Quaternion<T>
+where
+    T: Numeric,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.data
+    }
+}
+
+//composition, `a * b` applies b first then a
+impl<T> Mul for Quaternion<T>
+where
+    T: Numeric,
+    T: Add<Output = T>,
+    T: Sub<Output = T>,
+    T: Mul<Output = T>,
+{
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        let [ax, ay, az, aw] = self.data;
+        let [bx, by, bz, bw] = rhs.data;
+
+        Self {
+            data: [
+                aw * bx + ax * bw + ay * bz - az * by,
+                aw * by - ax * bz + ay * bw + az * bx,
+                aw * bz + ax * by - ay * bx + az * bw,
+                aw * bw - ax * bx - ay * by - az * bz,
+            ],
+        }
+    }
+}
+
+impl<T> MulAssign for Quaternion<T>
+where
+    T: Numeric,
+    T: Add<Output = T>,
+    T: Sub<Output = T>,
+    T: Mul<Output = T>,
+{
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+//rotates the vector, the quaternion is assumed to be normalized
+impl<T> Mul<Vector<T, 3>> for Quaternion<T>
+where
+    T: Numeric,
+    T: From<u8>,
+    T: Add<Output = T>,
+    T: Sub<Output = T>,
+    T: Mul<Output = T>,
+{
+    type Output = Vector<T, 3>;
+
+    fn mul(self, rhs: Vector<T, 3>) -> Self::Output {
+        let two = T::from(2);
+
+        let u = self.vector();
+        let t = u.cross(rhs) * two;
+
+        rhs + t * self.scalar() + u.cross(t)
+    }
+}
+
+//matrices are column major like glsl, so `matrix[3]` is the translation
+impl<T> From<Quaternion<T>> for Matrix<T, 4, 4>
+where
+    T: Numeric,
+    T: From<u8>,
+    T: Add<Output = T>,
+    T: Sub<Output = T>,
+    T: Mul<Output = T>,
+{
+    fn from(quaternion: Quaternion<T>) -> Self {
+        let [x, y, z, w] = quaternion.data;
+
+        let one = T::from(1);
+        let two = T::from(2);
+
+        let mut matrix = Matrix::identity();
+
+        matrix[0][0] = one - two * (y * y + z * z);
+        matrix[0][1] = two * (x * y + w * z);
+        matrix[0][2] = two * (x * z - w * y);
+
+        matrix[1][0] = two * (x * y - w * z);
+        matrix[1][1] = one - two * (x * x + z * z);
+        matrix[1][2] = two * (y * z + w * x);
+
+        matrix[2][0] = two * (x * z + w * y);
+        matrix[2][1] = two * (y * z - w * x);
+        matrix[2][2] = one - two * (x * x + y * y);
+
+        matrix
+    }
+}
+
+impl<T> Quaternion<T>
+where
+    T: Real,
+{
+    pub fn from_axis_angle(axis: Vector<T, 3>, angle: T) -> Self {
+        let half = angle / T::from(2);
+
+        let axis = axis / axis.dot(axis).sqrt() * half.sin();
+
+        Self {
+            data: [axis[0], axis[1], axis[2], half.cos()],
+        }
+    }
+
+    //same convention as the camera, yaw * pitch * roll about z, y and x
+    pub fn from_euler(euler: Vector<T, 3>) -> Self {
+        let half = |angle: T| angle / T::from(2);
+
+        let (sx, cx) = (half(euler[0]).sin(), half(euler[0]).cos());
+        let (sy, cy) = (half(euler[1]).sin(), half(euler[1]).cos());
+        let (sz, cz) = (half(euler[2]).sin(), half(euler[2]).cos());
+
+        Self {
+            data: [
+                sx * cy * cz - cx * sy * sz,
+                cx * sy * cz + sx * cy * sz,
+                cx * cy * sz - sx * sy * cz,
+                cx * cy * cz + sx * sy * sz,
+            ],
+        }
+    }
+
+    pub fn to_euler(self) -> Vector<T, 3> {
+        let [x, y, z, w] = self.data;
+
+        let one = T::from(1);
+        let two = T::from(2);
+
+        let roll = (two * (w * x + y * z)).atan2(one - two * (x * x + y * y));
+        //clamped so rounding at the poles doesn't produce nan
+        let pitch = clamp(two * (w * y - x * z), -one, one).asin();
+        let yaw = (two * (w * z + x * y)).atan2(one - two * (y * y + z * z));
+
+        Vector::new([roll, pitch, yaw])
+    }
+
+    pub fn magnitude(&self) -> T {
+        self.dot(*self).sqrt()
+    }
+
+    pub fn normalize(self) -> Self {
+        self / self.magnitude()
+    }
+
+    pub fn inverse(self) -> Self {
+        self.conjugate() / self.dot(self)
+    }
+
+    pub fn nlerp(self, to: Self, t: T) -> Self {
+        //take the short way around
+        let to = if self.dot(to) < T::default() {
+            to * -T::from(1)
+        } else {
+            to
+        };
+
+        (self * (T::from(1) - t) + to * t).normalize()
+    }
+
+    pub fn slerp(self, to: Self, t: T) -> Self {
+        let one = T::from(1);
+
+        let mut cos = self.dot(to);
+
+        //take the short way around
+        let to = if cos < T::default() {
+            cos = -cos;
+            to * -one
+        } else {
+            to
+        };
+
+        //nearly parallel, fall back to avoid dividing by sin of a tiny angle
+        if cos > one - T::EPSILON * T::from(16) {
+            return self.nlerp(to, t);
+        }
+
+        let angle = cos.acos();
+        let sin = angle.sin();
+
+        let from_factor = ((one - t) * angle).sin() / sin;
+        let to_factor = (t * angle).sin() / sin;
+
+        self * from_factor + to * to_factor
+    }
+}
+
+impl<T> From<Matrix<T, 4, 4>> for Quaternion<T>
+where
+    T: Real,
+{
+    fn from(matrix: Matrix<T, 4, 4>) -> Self {
+        //column major, so the rotation element at row r and column c is matrix[c][r]
+        let m = |r: usize, c: usize| matrix[c][r];
+
+        let one = T::from(1);
+        let two = T::from(2);
+        let four = T::from(4);
+
+        let trace = m(0, 0) + m(1, 1) + m(2, 2);
+
+        let data = if trace > T::default() {
+            let s = (trace + one).sqrt() * two;
+            [
+                (m(2, 1) - m(1, 2)) / s,
+                (m(0, 2) - m(2, 0)) / s,
+                (m(1, 0) - m(0, 1)) / s,
+                s / four,
+            ]
+        } else if m(0, 0) > m(1, 1) && m(0, 0) > m(2, 2) {
+            let s = (one + m(0, 0) - m(1, 1) - m(2, 2)).sqrt() * two;
+            [
+                s / four,
+                (m(0, 1) + m(1, 0)) / s,
+                (m(0, 2) + m(2, 0)) / s,
+                (m(2, 1) - m(1, 2)) / s,
+            ]
+        } else if m(1, 1) > m(2, 2) {
+            let s = (one + m(1, 1) - m(0, 0) - m(2, 2)).sqrt() * two;
+            [
+                (m(0, 1) + m(1, 0)) / s,
+                s / four,
+                (m(1, 2) + m(2, 1)) / s,
+                (m(0, 2) - m(2, 0)) / s,
+            ]
+        } else {
+            let s = (one + m(2, 2) - m(0, 0) - m(1, 1)).sqrt() * two;
+            [
+                (m(0, 2) + m(2, 0)) / s,
+                (m(1, 2) + m(2, 1)) / s,
+                s / four,
+                (m(1, 0) - m(0, 1)) / s,
+            ]
+        };
+
+        Self { data }.normalize()
+    }
+}
+
+impl<T> Add for Quaternion<T>
+where
+    T: Numeric,
+    T: Add<Output = T>,
+{
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        let mut data = self.data;
+        for i in 0..4 {
+            data[i] += rhs.data[i];
+        }
+        Self { data }
+    }
+}
+
+impl<T> Mul<T> for Quaternion<T>
+where
+    T: Numeric,
+    T: Mul<Output = T>,
+{
+    type Output = Self;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        let mut data = self.data;
+        for i in 0..4 {
+            data[i] = data[i] * rhs;
+        }
+        Self { data }
+    }
+}
+
+impl<T> Div<T> for Quaternion<T>
+where
+    T: Numeric,
+    T: Div<Output = T>,
+{
+    type Output = Self;
+
+    fn div(self, rhs: T) -> Self::Output {
+        let mut data = self.data;
+        for i in 0..4 {
+            data[i] /= rhs;
+        }
+        Self { data }
+    }
+}
+
+fn clamp<T: PartialOrd>(value: T, min: T, max: T) -> T {
+    if value < min {
+        min
+    } else if value > max {
+        max
+    } else {
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //rotations hitting every branch of the matrix conversion, including half turns
+    fn rotations() -> Vec<Quaternion<f32>> {
+        let axes = [
+            Vector::new([1.0, 0.0, 0.0]),
+            Vector::new([0.0, 1.0, 0.0]),
+            Vector::new([0.0, 0.0, 1.0]),
+            Vector::new([1.0, 2.0, -3.0]),
+            Vector::new([-0.5, 0.25, 1.0]),
+        ];
+
+        let angles = [0.0, 0.3, 1.5, 3.0, std::f32::consts::PI, -2.2];
+
+        let mut rotations = vec![];
+
+        for axis in axes {
+            for angle in angles {
+                rotations.push(Quaternion::from_axis_angle(axis, angle));
+            }
+        }
+
+        rotations.push(Quaternion::from_euler(Vector::new([0.4, -1.1, 2.5])));
+
+        rotations
+    }
+
+    #[test]
+    fn matrix_rotates_like_quaternion() {
+        let point = Vector::new([0.3, -1.7, 2.2]);
+
+        for rotation in rotations() {
+            let expected = rotation * point;
+
+            let actual = Matrix::from(rotation) * Vector::new([point[0], point[1], point[2], 1.0]);
+
+            for i in 0..3 {
+                assert!((actual[i] - expected[i]).abs() < 1e-5, "{:?}", rotation);
+            }
+
+            assert!((actual[3] - 1.0).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn matrix_round_trip() {
+        for rotation in rotations() {
+            let round_trip = Quaternion::from(Matrix::from(rotation));
+
+            //q and -q are the same rotation
+            assert!(round_trip.dot(rotation).abs() > 1.0 - 1e-5);
+        }
+    }
+
+    #[test]
+    fn matrix_composes_like_quaternion() {
+        let (a, b) = (rotations()[4], rotations()[20]);
+
+        let expected = Matrix::from(a * b);
+        let actual = Matrix::from(a) * Matrix::from(b);
+
+        for c in 0..4 {
+            for r in 0..4 {
+                assert!((actual[c][r] - expected[c][r]).abs() < 1e-5);
+            }
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use std::{fmt, marker::PhantomData};
+
+use serde::{
+    de::{self, SeqAccess, Visitor},
+    ser::SerializeTuple,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::{fixed::Fixed, matrix::Matrix, quaternion::Quaternion, vector::Vector, Numeric};
+
+//arrays are written as tuples, the length is part of the type so it isn't sent
+
+fn serialize_array<S, T, const N: usize>(serializer: S, data: &[T; N]) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: Serialize,
+{
+    let mut tuple = serializer.serialize_tuple(N)?;
+
+    for element in data {
+        tuple.serialize_element(element)?;
+    }
+
+    tuple.end()
+}
+
+fn deserialize_array<'de, D, T, const N: usize>(deserializer: D) -> Result<[T; N], D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de> + Copy + Default,
+{
+    deserializer.deserialize_tuple(N, ArrayVisitor(PhantomData))
+}
+
+struct ArrayVisitor<T, const N: usize>(PhantomData<T>);
+
+impl<'de, T, const N: usize> Visitor<'de> for ArrayVisitor<T, N>
+where
+    T: Deserialize<'de> + Copy + Default,
+{
+    type Value = [T; N];
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "an array of length {}", N)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut data = [T::default(); N];
+
+        for (i, element) in data.iter_mut().enumerate() {
+            let Some(value) = seq.next_element()? else {
+                Err(de::Error::invalid_length(i, &self))?
+            };
+
+            *element = value;
+        }
+
+        Ok(data)
+    }
+}
+
+impl<T, const N: usize> Serialize for Vector<T, N>
+where
+    T: Numeric,
+    T: Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_array(serializer, self)
+    }
+}
+
+impl<'de, T, const N: usize> Deserialize<'de> for Vector<T, N>
+where
+    T: Numeric,
+    T: Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_array(deserializer).map(Vector::new)
+    }
+}
+
+impl<T, const N: usize, const M: usize> Serialize for Matrix<T, N, M>
+where
+    T: Numeric,
+    T: Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_array(serializer, self)
+    }
+}
+
+impl<'de, T, const N: usize, const M: usize> Deserialize<'de> for Matrix<T, N, M>
+where
+    T: Numeric,
+    T: Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_array(deserializer).map(Matrix::new)
+    }
+}
+
+impl<T> Serialize for Quaternion<T>
+where
+    T: Numeric,
+    T: Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_array(serializer, self)
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Quaternion<T>
+where
+    T: Numeric,
+    T: Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_array(deserializer).map(Quaternion::new)
+    }
+}
+
+//the raw bits, so values round trip exactly
+impl Serialize for Fixed {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.to_bits().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Fixed {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        i64::deserialize(deserializer).map(Fixed::from_bits)
+    }
+}
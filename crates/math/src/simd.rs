@@ -0,0 +1,345 @@
+//specializations of the generic vector and matrix operations for the 4 wide f32 case
+//the generic loops don't vectorize, and these run on every camera and transform update
+
+use std::ops::{Add, Div, Mul, Sub};
+use std::simd::f32x4;
+
+use crate::{
+    matrix::{Invert, Matrix},
+    vector::Vector,
+};
+
+fn load(vector: Vector<f32, 4>) -> f32x4 {
+    f32x4::from_array(*vector)
+}
+
+fn store(simd: f32x4) -> Vector<f32, 4> {
+    Vector::new(simd.to_array())
+}
+
+impl Add for Vector<f32, 4> {
+    fn add(self, rhs: Self) -> Self::Output {
+        store(load(self) + load(rhs))
+    }
+}
+
+impl Sub for Vector<f32, 4> {
+    fn sub(self, rhs: Self) -> Self::Output {
+        store(load(self) - load(rhs))
+    }
+}
+
+impl Mul<f32> for Vector<f32, 4> {
+    fn mul(self, rhs: f32) -> Self::Output {
+        store(load(self) * f32x4::splat(rhs))
+    }
+}
+
+impl Div<f32> for Vector<f32, 4> {
+    fn div(self, rhs: f32) -> Self::Output {
+        store(load(self) / f32x4::splat(rhs))
+    }
+}
+
+impl Mul for Vector<f32, 4> {
+    fn mul(self, rhs: Self) -> Self::Output {
+        store(load(self) * load(rhs))
+    }
+}
+
+impl Div for Vector<f32, 4> {
+    fn div(self, rhs: Self) -> Self::Output {
+        store(load(self) / load(rhs))
+    }
+}
+
+//column c of the product is the columns of self weighted by column c of rhs
+impl Mul for Matrix<f32, 4, 4> {
+    fn mul(self, rhs: Self) -> Self::Output {
+        let columns = self.map(load);
+
+        Matrix::new(rhs.map(|column| {
+            store(
+                f32x4::splat(column[0]) * columns[0]
+                    + f32x4::splat(column[1]) * columns[1]
+                    + f32x4::splat(column[2]) * columns[2]
+                    + f32x4::splat(column[3]) * columns[3],
+            )
+        }))
+    }
+}
+
+impl Mul<Vector<f32, 4>> for Matrix<f32, 4, 4> {
+    fn mul(self, rhs: Vector<f32, 4>) -> Self::Output {
+        let columns = self.map(load);
+
+        store(
+            f32x4::splat(rhs[0]) * columns[0]
+                + f32x4::splat(rhs[1]) * columns[1]
+                + f32x4::splat(rhs[2]) * columns[2]
+                + f32x4::splat(rhs[3]) * columns[3],
+        )
+    }
+}
+
+//cofactor expansion through the 2x2 determinants of the top and bottom row pairs
+impl Invert for Matrix<f32, 4, 4> {
+    fn invert(self) -> Option<Self> {
+        if self.is_affine() {
+            return self.try_inverse_affine();
+        }
+
+        let a = |i: usize, j: usize| self[i][j];
+
+        let s = [
+            a(0, 0) * a(1, 1) - a(1, 0) * a(0, 1),
+            a(0, 0) * a(1, 2) - a(1, 0) * a(0, 2),
+            a(0, 0) * a(1, 3) - a(1, 0) * a(0, 3),
+            a(0, 1) * a(1, 2) - a(1, 1) * a(0, 2),
+            a(0, 1) * a(1, 3) - a(1, 1) * a(0, 3),
+            a(0, 2) * a(1, 3) - a(1, 2) * a(0, 3),
+        ];
+
+        let c = [
+            a(2, 0) * a(3, 1) - a(3, 0) * a(2, 1),
+            a(2, 0) * a(3, 2) - a(3, 0) * a(2, 2),
+            a(2, 0) * a(3, 3) - a(3, 0) * a(2, 3),
+            a(2, 1) * a(3, 2) - a(3, 1) * a(2, 2),
+            a(2, 1) * a(3, 3) - a(3, 1) * a(2, 3),
+            a(2, 2) * a(3, 3) - a(3, 2) * a(2, 3),
+        ];
+
+        let determinant =
+            s[0] * c[5] - s[1] * c[4] + s[2] * c[3] + s[3] * c[2] - s[4] * c[1] + s[5] * c[0];
+
+        if determinant == 0.0 {
+            None?
+        }
+
+        //column j of self gathered in the order rows 1, 0, 3, 2
+        let rows = self.map(load);
+        let column = |j: usize| f32x4::from_array([rows[1][j], rows[0][j], rows[3][j], rows[2][j]]);
+
+        let (c0, c1, c2, c3) = (column(0), column(1), column(2), column(3));
+
+        //the bottom cofactor feeds the first two lanes and the top the last two
+        let pair = |k: usize| f32x4::from_array([c[5 - k], c[5 - k], s[5 - k], s[5 - k]]);
+
+        let (p0, p1, p2, p3, p4, p5) = (pair(0), pair(1), pair(2), pair(3), pair(4), pair(5));
+
+        let even = f32x4::from_array([1.0, -1.0, 1.0, -1.0]) / f32x4::splat(determinant);
+        let odd = -even;
+
+        Some(Matrix::new([
+            store(even * (c1 * p0 - c2 * p1 + c3 * p2)),
+            store(odd * (c0 * p0 - c2 * p3 + c3 * p4)),
+            store(even * (c0 * p1 - c1 * p3 + c3 * p5)),
+            store(odd * (c0 * p2 - c1 * p4 + c2 * p5)),
+        ]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate test;
+
+    use super::*;
+
+    use test::{black_box, Bencher};
+
+    const SAMPLES: usize = 1000;
+    //how far past epsilon times the condition number an inverse may be off
+    const BOUND: f64 = 8.0;
+
+    //xorshift, so failures reproduce
+    struct Random(u64);
+
+    impl Random {
+        fn next(&mut self) -> f32 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+
+            (self.0 >> 40) as f32 / (1u64 << 23) as f32 - 1.0
+        }
+
+        fn vector(&mut self) -> Vector<f32, 4> {
+            Vector::new([self.next(), self.next(), self.next(), self.next()])
+        }
+
+        fn matrix(&mut self) -> Matrix<f32, 4, 4> {
+            Matrix::new([self.vector(), self.vector(), self.vector(), self.vector()])
+        }
+
+        //the last column is a blend of the others plus a little noise
+        fn near_singular(&mut self, noise: f32) -> Matrix<f32, 4, 4> {
+            let mut matrix = self.matrix();
+
+            matrix[3] = matrix[0] * self.next() + matrix[1] * self.next() + self.vector() * noise;
+
+            matrix
+        }
+    }
+
+    //within a tolerance relative to the largest element, which is what near singular inverses
+    //lose precision against
+    fn assert_near(simd: Matrix<f32, 4, 4>, generic: Matrix<f32, 4, 4>, tolerance: f32) {
+        let scale = generic
+            .iter()
+            .flat_map(|column| column.iter())
+            .fold(1.0f32, |scale, x| scale.max(x.abs()));
+
+        for c in 0..4 {
+            for r in 0..4 {
+                assert!(
+                    (simd[c][r] - generic[c][r]).abs() <= tolerance * scale,
+                    "{:?} != {:?}",
+                    simd,
+                    generic
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn mul_matches_generic() {
+        let mut random = Random(0x2545_f491_4f6c_dd1d);
+
+        for _ in 0..SAMPLES {
+            let (a, b, v) = (random.matrix(), random.matrix(), random.vector());
+
+            assert_near(a * b, a.multiply(b), 1e-6);
+
+            let (simd, generic) = (a * v, a.transform(v));
+
+            for i in 0..4 {
+                assert!((simd[i] - generic[i]).abs() <= 1e-6);
+            }
+        }
+    }
+
+    #[test]
+    fn invert_matches_gauss_jordan() {
+        let mut random = Random(0x9e37_79b9_7f4a_7c15);
+
+        for _ in 0..SAMPLES {
+            let matrix = random.matrix();
+
+            let (Some(simd), Some(generic)) = (matrix.invert(), matrix.gauss_jordan()) else {
+                panic!("{:?} is invertible", matrix);
+            };
+
+            assert_near(simd, generic, 1e-3);
+        }
+    }
+
+    fn widen(matrix: Matrix<f32, 4, 4>) -> Matrix<f64, 4, 4> {
+        Matrix::new(matrix.map(|column| Vector::new(column.map(|x| x as f64))))
+    }
+
+    //the largest row sum
+    fn norm(matrix: Matrix<f64, 4, 4>) -> f64 {
+        (0..4)
+            .map(|r| (0..4).map(|c| matrix[c][r].abs()).sum::<f64>())
+            .fold(0.0, f64::max)
+    }
+
+    //relative to an f64 inverse, the error an f32 inverse can't avoid grows with the condition
+    //number, so both paths are held to the same bound rather than compared directly
+    #[test]
+    fn invert_near_singular_matches_gauss_jordan() {
+        let mut random = Random(0x1234_5678_9abc_def1);
+
+        for noise in [1e-2, 1e-3, 1e-4] {
+            for _ in 0..SAMPLES {
+                let matrix = random.near_singular(noise);
+
+                let exact = widen(matrix).gauss_jordan().unwrap();
+
+                let condition = norm(widen(matrix)) * norm(exact);
+
+                //past this f32 can't tell the matrix from a singular one, either answer goes
+                if condition * f32::EPSILON as f64 > 0.01 {
+                    continue;
+                }
+
+                let bound = BOUND * f32::EPSILON as f64 * condition;
+
+                let error = |inverse: Matrix<f32, 4, 4>| {
+                    let mut difference = widen(inverse);
+
+                    difference -= exact;
+
+                    norm(difference) / norm(exact)
+                };
+
+                let (Some(simd), Some(generic)) = (matrix.invert(), matrix.gauss_jordan()) else {
+                    panic!("{:?} is invertible", matrix);
+                };
+
+                assert!(error(simd) <= bound, "{:?}", matrix);
+                assert!(error(generic) <= bound, "{:?}", matrix);
+            }
+        }
+    }
+
+    #[test]
+    fn invert_singular() {
+        let mut random = Random(0x0f0f_0f0f_0f0f_0f0f);
+
+        for column in 0..4 {
+            let mut matrix = random.matrix();
+
+            matrix[column] = Vector::new([0.0; 4]);
+
+            assert!(matrix.invert().is_none());
+            assert!(matrix.gauss_jordan().is_none());
+        }
+    }
+
+    #[bench]
+    fn bench_mul_simd(bencher: &mut Bencher) {
+        let mut random = Random(1);
+        let (a, b) = (random.matrix(), random.matrix());
+
+        bencher.iter(|| black_box(a) * black_box(b));
+    }
+
+    #[bench]
+    fn bench_mul_generic(bencher: &mut Bencher) {
+        let mut random = Random(1);
+        let (a, b) = (random.matrix(), random.matrix());
+
+        bencher.iter(|| black_box(a).multiply(black_box(b)));
+    }
+
+    #[bench]
+    fn bench_transform_simd(bencher: &mut Bencher) {
+        let mut random = Random(1);
+        let (a, v) = (random.matrix(), random.vector());
+
+        bencher.iter(|| black_box(a) * black_box(v));
+    }
+
+    #[bench]
+    fn bench_transform_generic(bencher: &mut Bencher) {
+        let mut random = Random(1);
+        let (a, v) = (random.matrix(), random.vector());
+
+        bencher.iter(|| black_box(a).transform(black_box(v)));
+    }
+
+    #[bench]
+    fn bench_invert_simd(bencher: &mut Bencher) {
+        let matrix = Random(1).matrix();
+
+        bencher.iter(|| black_box(matrix).invert());
+    }
+
+    #[bench]
+    fn bench_invert_gauss_jordan(bencher: &mut Bencher) {
+        let matrix = Random(1).matrix();
+
+        bencher.iter(|| black_box(matrix).gauss_jordan());
+    }
+}
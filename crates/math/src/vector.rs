@@ -1,9 +1,20 @@
 #![allow(clippy::needless_range_loop)]
 
 use std::convert::FloatToInt;
-use std::ops::{Add, AddAssign, Deref, DerefMut, Div, DivAssign, Mul, MulAssign, Sub, SubAssign};
-
-use crate::Numeric;
+use std::ops::{
+    Add, AddAssign, Deref, DerefMut, Div, DivAssign, Index, IndexMut, Mul, MulAssign, Neg, Sub,
+    SubAssign,
+};
+
+use crate::{Numeric, Real};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Axis {
+    X = 0,
+    Y = 1,
+    Z = 2,
+    W = 3,
+}
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(transparent)]
@@ -208,6 +219,153 @@ where
     }
 }
 
+impl<T, const N: usize> Vector<T, N>
+where
+    T: Numeric,
+{
+    pub fn splat(value: T) -> Self {
+        Self { data: [value; N] }
+    }
+
+    //swizzles panic if the vector has fewer components than they read
+    pub fn swizzle<const M: usize>(self, indices: [usize; M]) -> Vector<T, M> {
+        Vector::new(indices.map(|i| self.data[i]))
+    }
+
+    pub fn x(self) -> T {
+        self.data[0]
+    }
+
+    pub fn y(self) -> T {
+        self.data[1]
+    }
+
+    pub fn z(self) -> T {
+        self.data[2]
+    }
+
+    pub fn w(self) -> T {
+        self.data[3]
+    }
+
+    pub fn xy(self) -> Vector<T, 2> {
+        self.swizzle([0, 1])
+    }
+
+    pub fn xz(self) -> Vector<T, 2> {
+        self.swizzle([0, 2])
+    }
+
+    pub fn yz(self) -> Vector<T, 2> {
+        self.swizzle([1, 2])
+    }
+
+    pub fn xyz(self) -> Vector<T, 3> {
+        self.swizzle([0, 1, 2])
+    }
+}
+
+impl<T, const N: usize> Vector<T, N>
+where
+    T: Numeric,
+    T: Sub<Output = T>,
+    T: PartialOrd,
+{
+    pub fn min(self, rhs: Self) -> Self {
+        let mut data = self.data;
+        for i in 0..N {
+            if rhs.data[i] < data[i] {
+                data[i] = rhs.data[i];
+            }
+        }
+        Self { data }
+    }
+
+    pub fn max(self, rhs: Self) -> Self {
+        let mut data = self.data;
+        for i in 0..N {
+            if rhs.data[i] > data[i] {
+                data[i] = rhs.data[i];
+            }
+        }
+        Self { data }
+    }
+
+    pub fn clamp(self, min: Self, max: Self) -> Self {
+        self.max(min).min(max)
+    }
+
+    pub fn abs(self) -> Self {
+        let mut data = self.data;
+        for i in 0..N {
+            if data[i] < T::default() {
+                data[i] = T::default() - data[i];
+            }
+        }
+        Self { data }
+    }
+}
+
+impl<T, const N: usize> Vector<T, N>
+where
+    T: Numeric,
+    T: Add<Output = T>,
+    T: Sub<Output = T>,
+    T: Mul<Output = T>,
+{
+    pub fn lerp(self, to: Self, t: T) -> Self {
+        self + (to - self) * t
+    }
+}
+
+impl<T, const N: usize> Vector<T, N>
+where
+    T: Real,
+{
+    pub fn floor(self) -> Self {
+        Self {
+            data: self.data.map(T::floor),
+        }
+    }
+
+    pub fn ceil(self) -> Self {
+        Self {
+            data: self.data.map(T::ceil),
+        }
+    }
+}
+
+//rounds towards negative infinity, so -1 / 16 is -1 rather than 0 for chunk coordinates
+macro_rules! impl_integer {
+    ($t:ty) => {
+        impl<const N: usize> Vector<$t, N> {
+            pub fn div_floor(self, rhs: $t) -> Self {
+                let mut data = self.data;
+                for i in 0..N {
+                    let quotient = data[i] / rhs;
+                    let remainder = data[i] % rhs;
+                    data[i] = if remainder != 0 && (remainder < 0) != (rhs < 0) {
+                        quotient - 1
+                    } else {
+                        quotient
+                    };
+                }
+                Self { data }
+            }
+
+            pub fn rem_euclid(self, rhs: $t) -> Self {
+                Self {
+                    data: self.data.map(|value| value.rem_euclid(rhs)),
+                }
+            }
+        }
+    };
+}
+
+impl_integer!(i32);
+impl_integer!(i64);
+impl_integer!(isize);
+
 impl<T, const N: usize> Add for Vector<T, N>
 where
     T: Numeric,
@@ -215,7 +373,7 @@ where
 {
     type Output = Self;
 
-    fn add(self, rhs: Self) -> Self::Output {
+    default fn add(self, rhs: Self) -> Self::Output {
         let mut data = [T::default(); N];
         for i in 0..N {
             data[i] = self.data[i] + rhs.data[i];
@@ -241,7 +399,7 @@ where
 {
     type Output = Self;
 
-    fn sub(self, rhs: Self) -> Self::Output {
+    default fn sub(self, rhs: Self) -> Self::Output {
         let mut data = [T::default(); N];
         for i in 0..N {
             data[i] = self.data[i] - rhs.data[i];
@@ -266,7 +424,7 @@ where
     T: Mul<Output = T>,
 {
     type Output = Self;
-    fn mul(self, rhs: T) -> Self::Output {
+    default fn mul(self, rhs: T) -> Self::Output {
         let mut data = self.data;
         for i in 0..N {
             data[i] = data[i] * rhs;
@@ -291,7 +449,7 @@ where
     T: Div<Output = T>,
 {
     type Output = Self;
-    fn div(self, rhs: T) -> Self::Output {
+    default fn div(self, rhs: T) -> Self::Output {
         let mut data = self.data;
         for i in 0..N {
             data[i] = data[i] / rhs;
@@ -309,3 +467,111 @@ where
         *self = *self / rhs;
     }
 }
+
+impl<T, const N: usize> Neg for Vector<T, N>
+where
+    T: Numeric,
+    T: Neg<Output = T>,
+{
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self {
+            data: self.data.map(T::neg),
+        }
+    }
+}
+
+//component wise
+impl<T, const N: usize> Mul for Vector<T, N>
+where
+    T: Numeric,
+    T: Mul<Output = T>,
+{
+    type Output = Self;
+
+    default fn mul(self, rhs: Self) -> Self::Output {
+        let mut data = self.data;
+        for i in 0..N {
+            data[i] = data[i] * rhs.data[i];
+        }
+        Self { data }
+    }
+}
+
+impl<T, const N: usize> MulAssign for Vector<T, N>
+where
+    T: Numeric,
+    T: Mul<Output = T>,
+{
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+//component wise
+impl<T, const N: usize> Div for Vector<T, N>
+where
+    T: Numeric,
+    T: Div<Output = T>,
+{
+    type Output = Self;
+
+    default fn div(self, rhs: Self) -> Self::Output {
+        let mut data = self.data;
+        for i in 0..N {
+            data[i] /= rhs.data[i];
+        }
+        Self { data }
+    }
+}
+
+impl<T, const N: usize> DivAssign for Vector<T, N>
+where
+    T: Numeric,
+    T: Div<Output = T>,
+{
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs;
+    }
+}
+
+impl<T, const N: usize> Index<usize> for Vector<T, N>
+where
+    T: Numeric,
+{
+    type Output = T;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.data[index]
+    }
+}
+
+impl<T, const N: usize> IndexMut<usize> for Vector<T, N>
+where
+    T: Numeric,
+{
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        &mut self.data[index]
+    }
+}
+
+impl<T, const N: usize> Index<Axis> for Vector<T, N>
+where
+    T: Numeric,
+{
+    type Output = T;
+
+    fn index(&self, axis: Axis) -> &Self::Output {
+        &self.data[axis as usize]
+    }
+}
+
+impl<T, const N: usize> IndexMut<Axis> for Vector<T, N>
+where
+    T: Numeric,
+{
+    fn index_mut(&mut self, axis: Axis) -> &mut Self::Output {
+        &mut self.data[axis as usize]
+    }
+}
@@ -0,0 +1,766 @@
+use std::collections::VecDeque;
+use std::net;
+use std::time::{Duration, Instant};
+
+use crate::{Deserializable, Error, Result, Serializable, Socket, MAX_DATAGRAM_SIZE};
+
+//sent packets remembered for acks, older ones count as lost
+const SENT_BUFFER_SIZE: usize = 1024;
+//reliable messages that can be in flight, and buffered out of order on the receiving side
+const RELIABLE_WINDOW: u16 = 256;
+//keeps packets under a typical mtu so they aren't fragmented
+const PACKET_BUDGET: usize = 1200;
+const HEADER_SIZE: usize = 8;
+//a channel byte, the reliable id and the length in front of each message
+const RELIABLE_HEADER_SIZE: usize = 5;
+const UNRELIABLE_HEADER_SIZE: usize = 3;
+
+//the largest message that can be sent, it goes alone in a packet with room left for the header
+//and a caller's own framing around the packet
+pub const MAX_MESSAGE_SIZE: usize = MAX_DATAGRAM_SIZE - 64;
+
+const INITIAL_RTT: Duration = Duration::from_millis(100);
+const MIN_RESEND_DELAY: Duration = Duration::from_millis(30);
+const MAX_RESEND_DELAY: Duration = Duration::from_secs(1);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Channel {
+    //delivered at most once, in any order
+    Unreliable,
+    //delivered exactly once, in the order sent
+    ReliableOrdered,
+}
+
+struct SentPacket {
+    sequence: u16,
+    time: Instant,
+    acked: bool,
+    reliable: Vec<u16>,
+}
+
+struct Reliable {
+    id: u16,
+    bytes: Vec<u8>,
+    sent: Option<Instant>,
+}
+
+//one end of a conversation with a single peer
+//packets carry a sequence number, the latest remote sequence seen and a bitfield of the 32
+//before it, so every packet acks the ones the other side sent recently
+//the socket is not owned, so a server can drive many connections from one socket
+pub struct Connection<T> {
+    address: net::SocketAddr,
+
+    local_sequence: u16,
+    sent: Vec<Option<SentPacket>>,
+
+    remote_sequence: Option<u16>,
+    received_bits: u32,
+    ack_pending: bool,
+
+    rtt: Duration,
+    rtt_variance: Duration,
+    rtt_sampled: bool,
+
+    unreliable: VecDeque<Vec<u8>>,
+
+    reliable: VecDeque<Reliable>,
+    next_reliable_id: u16,
+
+    next_delivery_id: u16,
+    out_of_order: Vec<Option<T>>,
+
+    received: VecDeque<T>,
+
+    loss: f32,
+    random: u64,
+}
+
+impl<T> Connection<T>
+where
+    T: Serializable + Deserializable,
+{
+    pub fn new(address: net::SocketAddr) -> Self {
+        Self {
+            address,
+
+            local_sequence: 0,
+            sent: (0..SENT_BUFFER_SIZE).map(|_| None).collect(),
+
+            remote_sequence: None,
+            received_bits: 0,
+            ack_pending: false,
+
+            rtt: INITIAL_RTT,
+            rtt_variance: INITIAL_RTT / 2,
+            rtt_sampled: false,
+
+            unreliable: VecDeque::new(),
+
+            reliable: VecDeque::new(),
+            next_reliable_id: 0,
+
+            next_delivery_id: 0,
+            out_of_order: (0..RELIABLE_WINDOW).map(|_| None).collect(),
+
+            received: VecDeque::new(),
+
+            loss: 0.0,
+            random: 0x9e37_79b9_7f4a_7c15 ^ address.port() as u64,
+        }
+    }
+
+    pub fn address(&self) -> net::SocketAddr {
+        self.address
+    }
+
+    //smoothed round trip time, measured from acks
+    pub fn rtt(&self) -> Duration {
+        self.rtt
+    }
+
+    //reliable messages sent but not acked yet
+    pub fn unacked(&self) -> usize {
+        self.reliable.len()
+    }
+
+    //drops this fraction of outgoing packets, for trying out the protocol on a bad network
+    pub fn set_loss(&mut self, loss: f32) {
+        self.loss = loss;
+    }
+
+    //queues a message for the next update, messages over MAX_MESSAGE_SIZE are refused
+    pub fn send(&mut self, channel: Channel, message: T) -> Result<()> {
+        let mut bytes = vec![];
+
+        message.serialize(&mut bytes);
+
+        if bytes.len() > MAX_MESSAGE_SIZE {
+            Err(Error::MessageTooLong)?
+        }
+
+        match channel {
+            Channel::Unreliable => self.unreliable.push_back(bytes),
+            Channel::ReliableOrdered => {
+                self.reliable.push_back(Reliable {
+                    id: self.next_reliable_id,
+                    bytes,
+                    sent: None,
+                });
+
+                self.next_reliable_id = self.next_reliable_id.wrapping_add(1);
+            }
+        }
+
+        Ok(())
+    }
+
+    //messages that have arrived, reliable ones in order
+    pub fn messages(&mut self) -> impl Iterator<Item = T> + '_ {
+        self.received.drain(..)
+    }
+
+    //handles a datagram the socket received from this connection's address, a packet that
+    //doesn't parse is rejected whole, nothing in it is acked or delivered
+    pub fn receive(&mut self, bytes: &[u8], now: Instant) -> Result<()> {
+        let mut reader = Reader { bytes };
+
+        let sequence = reader.u16()?;
+        let ack = reader.u16()?;
+        let ack_bits = reader.u32()?;
+
+        let mut messages = vec![];
+
+        while !reader.bytes.is_empty() {
+            let channel = match reader.u8()? {
+                0 => Channel::Unreliable,
+                1 => Channel::ReliableOrdered,
+                _ => Err(Error::InvalidData)?,
+            };
+
+            let id = match channel {
+                Channel::Unreliable => 0,
+                Channel::ReliableOrdered => reader.u16()?,
+            };
+
+            let len = reader.u16()? as usize;
+            let message = T::deserialize(reader.take(len)?).ok_or(Error::InvalidData)?;
+
+            messages.push((channel, id, message));
+        }
+
+        let duplicate = self.mark_received(sequence);
+
+        self.process_acks(ack, ack_bits, now);
+
+        for (channel, id, message) in messages {
+            match channel {
+                //a resent packet would otherwise deliver these twice
+                Channel::Unreliable if !duplicate => self.received.push_back(message),
+                Channel::Unreliable => {}
+                Channel::ReliableOrdered => {
+                    let offset = id.wrapping_sub(self.next_delivery_id);
+
+                    //already delivered, or too far ahead to buffer, it will be resent
+                    if offset < RELIABLE_WINDOW {
+                        self.out_of_order[id as usize % RELIABLE_WINDOW as usize] = Some(message);
+                    }
+                }
+            }
+        }
+
+        while let Some(message) =
+            self.out_of_order[self.next_delivery_id as usize % RELIABLE_WINDOW as usize].take()
+        {
+            self.received.push_back(message);
+            self.next_delivery_id = self.next_delivery_id.wrapping_add(1);
+        }
+
+        self.ack_pending = true;
+
+        Ok(())
+    }
+
+    //sends the next packet straight to the peer
+    pub fn update(&mut self, socket: &Socket, now: Instant) -> Result<()> {
+        let Some(bytes) = self.packet(now) else {
+            return Ok(());
+        };
+
+        socket.send_lossy(self.address, bytes)
+    }
+
+    //the next packet, with the queued messages, reliable ones that are due for a resend and
+    //acks for what has been received, none when there is nothing to say or the simulated loss
+    //dropped it, for callers that wrap the bytes in their own framing before sending
+    pub fn packet(&mut self, now: Instant) -> Option<Vec<u8>> {
+        let mut bytes = Vec::with_capacity(PACKET_BUDGET);
+
+        bytes.extend_from_slice(&self.local_sequence.to_le_bytes());
+        bytes.extend_from_slice(&self.remote_sequence.unwrap_or(u16::MAX).to_le_bytes());
+        bytes.extend_from_slice(&self.received_bits.to_le_bytes());
+
+        let resend_delay = self.resend_delay();
+        let oldest = self.reliable.front().map_or(0, |message| message.id);
+
+        let mut reliable = vec![];
+
+        for message in &mut self.reliable {
+            //the receiver can't buffer further ahead than the window
+            if message.id.wrapping_sub(oldest) >= RELIABLE_WINDOW {
+                break;
+            }
+
+            let due = message
+                .sent
+                .is_none_or(|sent| now.duration_since(sent) >= resend_delay);
+
+            //a message too big for any packet still goes out alone
+            let fits = bytes.len() + RELIABLE_HEADER_SIZE + message.bytes.len() <= PACKET_BUDGET
+                || bytes.len() == HEADER_SIZE;
+
+            if !due || !fits {
+                continue;
+            }
+
+            bytes.push(1);
+            bytes.extend_from_slice(&message.id.to_le_bytes());
+            bytes.extend_from_slice(&(message.bytes.len() as u16).to_le_bytes());
+            bytes.extend_from_slice(&message.bytes);
+
+            message.sent = Some(now);
+            reliable.push(message.id);
+        }
+
+        while let Some(message) = self.unreliable.front() {
+            let fits = bytes.len() + UNRELIABLE_HEADER_SIZE + message.len() <= PACKET_BUDGET
+                || bytes.len() == HEADER_SIZE;
+
+            if !fits {
+                break;
+            }
+
+            let message = self.unreliable.pop_front().unwrap();
+
+            bytes.push(0);
+            bytes.extend_from_slice(&(message.len() as u16).to_le_bytes());
+            bytes.extend_from_slice(&message);
+        }
+
+        if bytes.len() == HEADER_SIZE && !self.ack_pending {
+            return None;
+        }
+
+        let sequence = self.local_sequence;
+
+        self.sent[sequence as usize % SENT_BUFFER_SIZE] = Some(SentPacket {
+            sequence,
+            time: now,
+            acked: false,
+            reliable,
+        });
+
+        self.local_sequence = self.local_sequence.wrapping_add(1);
+        self.ack_pending = false;
+
+        if self.next_random() < self.loss {
+            return None;
+        }
+
+        Some(bytes)
+    }
+
+    //returns whether the packet was seen before
+    fn mark_received(&mut self, sequence: u16) -> bool {
+        let Some(remote_sequence) = self.remote_sequence else {
+            self.remote_sequence = Some(sequence);
+            return false;
+        };
+
+        if sequence_greater(sequence, remote_sequence) {
+            let shift = sequence.wrapping_sub(remote_sequence) as u32;
+
+            self.received_bits = match shift {
+                1..=31 => (self.received_bits << shift) | 1 << (shift - 1),
+                32 => 1 << 31,
+                _ => 0,
+            };
+
+            self.remote_sequence = Some(sequence);
+
+            return false;
+        }
+
+        let age = remote_sequence.wrapping_sub(sequence) as u32;
+
+        //too old to tell, reliable messages are still deduplicated by id
+        if age == 0 || age > 32 {
+            return age == 0;
+        }
+
+        let bit = 1 << (age - 1);
+        let duplicate = self.received_bits & bit != 0;
+
+        self.received_bits |= bit;
+
+        duplicate
+    }
+
+    fn process_acks(&mut self, ack: u16, ack_bits: u32, now: Instant) {
+        let acked = (0..32)
+            .filter(|i| ack_bits & 1 << i != 0)
+            .map(|i| ack.wrapping_sub(i + 1))
+            .chain([ack]);
+
+        for sequence in acked {
+            let Some(packet) = &mut self.sent[sequence as usize % SENT_BUFFER_SIZE] else {
+                continue;
+            };
+
+            if packet.sequence != sequence || packet.acked {
+                continue;
+            }
+
+            packet.acked = true;
+
+            let sample = now.duration_since(packet.time);
+            let reliable = std::mem::take(&mut packet.reliable);
+
+            self.sample_rtt(sample);
+
+            self.reliable
+                .retain(|message| !reliable.contains(&message.id));
+        }
+    }
+
+    //the same smoothing as tcp, rfc 6298
+    fn sample_rtt(&mut self, sample: Duration) {
+        if !self.rtt_sampled {
+            self.rtt = sample;
+            self.rtt_variance = sample / 2;
+            self.rtt_sampled = true;
+            return;
+        }
+
+        let deviation = sample.abs_diff(self.rtt);
+
+        self.rtt_variance = self.rtt_variance * 3 / 4 + deviation / 4;
+        self.rtt = self.rtt * 7 / 8 + sample / 8;
+    }
+
+    fn resend_delay(&self) -> Duration {
+        (self.rtt + self.rtt_variance * 4).clamp(MIN_RESEND_DELAY, MAX_RESEND_DELAY)
+    }
+
+    //xorshift, only used for simulated loss
+    fn next_random(&mut self) -> f32 {
+        self.random ^= self.random << 13;
+        self.random ^= self.random >> 7;
+        self.random ^= self.random << 17;
+
+        (self.random >> 40) as f32 / (1u64 << 24) as f32
+    }
+}
+
+//whether a is after b, allowing for wrap around
+fn sequence_greater(a: u16, b: u16) -> bool {
+    a != b && a.wrapping_sub(b) < 1 << 15
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        if self.bytes.len() < len {
+            Err(Error::InvalidData)?
+        }
+
+        let (taken, rest) = self.bytes.split_at(len);
+
+        self.bytes = rest;
+
+        Ok(taken)
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16> {
+        Ok(u16::from_le_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::SocketType;
+
+    use std::task::Poll;
+    use std::thread;
+
+    const STEP: Duration = Duration::from_millis(10);
+    const MAX_STEPS: usize = 100_000;
+
+    type Pair = (Connection<Vec<u8>>, Connection<Vec<u8>>);
+
+    fn pair(loss: f32) -> Pair {
+        let mut a = Connection::new("127.0.0.1:1".parse().unwrap());
+        let mut b = Connection::new("127.0.0.1:2".parse().unwrap());
+
+        a.set_loss(loss);
+        b.set_loss(loss);
+
+        (a, b)
+    }
+
+    //the index up front, padded out to `len`
+    fn message(index: u32, len: usize) -> Vec<u8> {
+        let mut bytes = index.to_le_bytes().to_vec();
+
+        bytes.resize(len.max(4), index as u8);
+
+        bytes
+    }
+
+    fn index(message: &[u8]) -> u32 {
+        u32::from_le_bytes(message[..4].try_into().unwrap())
+    }
+
+    //one step of both sides sending what they have, `forward` and `back` let packets through
+    fn step((a, b): &mut Pair, now: Instant, forward: bool, back: bool) {
+        while let Some(bytes) = a.packet(now) {
+            if forward {
+                b.receive(&bytes, now).unwrap();
+            }
+        }
+
+        while let Some(bytes) = b.packet(now) {
+            if back {
+                a.receive(&bytes, now).unwrap();
+            }
+        }
+    }
+
+    //steps until everything sent has been delivered and acked
+    fn settle(pair: &mut Pair, now: &mut Instant, received: &mut Vec<u32>, count: usize) {
+        for _ in 0..MAX_STEPS {
+            if received.len() >= count && pair.0.unacked() == 0 {
+                return;
+            }
+
+            step(pair, *now, true, true);
+
+            received.extend(pair.1.messages().map(|message| index(&message)));
+
+            *now += STEP;
+        }
+
+        panic!("{} of {} delivered", received.len(), count);
+    }
+
+    #[test]
+    fn reliable_under_loss() {
+        let mut pair = pair(0.3);
+
+        let mut now = Instant::now();
+        let mut received = vec![];
+
+        let count = 2000;
+
+        for i in 0..count {
+            //every so often one too big to share a packet
+            let len = if i % 100 == 0 { 3 * PACKET_BUDGET } else { 16 };
+
+            pair.0
+                .send(Channel::ReliableOrdered, message(i, len))
+                .unwrap();
+        }
+
+        settle(&mut pair, &mut now, &mut received, count as usize);
+
+        assert_eq!(received, (0..count).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn unreliable_at_most_once() {
+        let mut pair = pair(0.3);
+
+        let mut now = Instant::now();
+        let mut received = vec![];
+
+        for i in 0..1000 {
+            pair.0.send(Channel::Unreliable, message(i, 16)).unwrap();
+
+            step(&mut pair, now, true, true);
+
+            received.extend(pair.1.messages().map(|message| index(&message)));
+
+            now += STEP;
+        }
+
+        let mut unique = received.clone();
+
+        unique.sort();
+        unique.dedup();
+
+        //lost ones stay lost, but nothing is delivered twice or made up
+        assert_eq!(unique.len(), received.len());
+        assert!(received.len() < 1000 && received.len() > 500);
+        assert!(received.iter().all(|&i| i < 1000));
+    }
+
+    #[test]
+    fn sequence_wraparound() {
+        let mut pair = pair(0.3);
+
+        let start = u16::MAX - 50;
+
+        pair.0.local_sequence = start;
+        pair.0.next_reliable_id = start;
+        pair.1.local_sequence = start;
+        pair.1.next_delivery_id = start;
+
+        let mut now = Instant::now();
+        let mut received = vec![];
+
+        let count = 1000;
+
+        //a few at a time, so both sequences and reliable ids run past u16::MAX
+        for i in 0..count {
+            pair.0
+                .send(Channel::ReliableOrdered, message(i, 16))
+                .unwrap();
+
+            if i % 4 == 0 {
+                step(&mut pair, now, true, true);
+
+                received.extend(pair.1.messages().map(|message| index(&message)));
+
+                now += STEP;
+            }
+        }
+
+        settle(&mut pair, &mut now, &mut received, count as usize);
+
+        assert!(pair.0.local_sequence < start && pair.0.next_reliable_id < start);
+        assert!(pair.1.local_sequence < start && pair.1.next_delivery_id < start);
+
+        assert_eq!(received, (0..count).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn ack_window_overflow() {
+        let mut pair = pair(0.0);
+
+        let mut now = Instant::now();
+        let mut received = vec![];
+
+        let mut sent = 0;
+
+        //more packets than the ack bits cover go unacked, with more reliable messages in
+        //flight than the receiver can buffer, first without acks coming back and then
+        //without packets getting there
+        for (forward, back) in [(true, false), (false, true)] {
+            for _ in 0..200 {
+                for _ in 0..5 {
+                    pair.0
+                        .send(Channel::ReliableOrdered, message(sent, 16))
+                        .unwrap();
+
+                    sent += 1;
+                }
+
+                step(&mut pair, now, forward, back);
+
+                received.extend(pair.1.messages().map(|message| index(&message)));
+
+                now += STEP;
+            }
+        }
+
+        assert!(pair.0.unacked() > RELIABLE_WINDOW as usize);
+
+        settle(&mut pair, &mut now, &mut received, sent as usize);
+
+        assert_eq!(received, (0..sent).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn message_too_long() {
+        let mut pair = pair(0.0);
+
+        let error = pair
+            .0
+            .send(Channel::ReliableOrdered, vec![0; MAX_MESSAGE_SIZE + 1]);
+
+        assert!(matches!(error, Err(Error::MessageTooLong)));
+
+        pair.0
+            .send(Channel::ReliableOrdered, message(7, MAX_MESSAGE_SIZE))
+            .unwrap();
+
+        let mut now = Instant::now();
+        let mut received = vec![];
+
+        settle(&mut pair, &mut now, &mut received, 1);
+
+        assert_eq!(received, [7]);
+    }
+
+    #[test]
+    fn corrupt_packet_is_rejected_whole() {
+        let mut pair = pair(0.0);
+
+        let now = Instant::now();
+
+        for i in 0..2 {
+            pair.0
+                .send(Channel::ReliableOrdered, message(i, 16))
+                .unwrap();
+        }
+
+        let bytes = pair.0.packet(now).unwrap();
+
+        //the first message is intact, the second is cut short
+        let truncated = &bytes[..bytes.len() - 1];
+
+        //the second message's channel byte is made up
+        let mut corrupt = bytes.clone();
+        corrupt[HEADER_SIZE + RELIABLE_HEADER_SIZE + 16] = 7;
+
+        for bytes in [truncated, &corrupt] {
+            assert!(pair.1.receive(bytes, now).is_err());
+
+            assert_eq!(pair.1.remote_sequence, None);
+            assert_eq!(pair.1.messages().count(), 0);
+            assert!(pair.1.packet(now).is_none());
+        }
+
+        //the intact packet still goes through
+        pair.1.receive(&bytes, now).unwrap();
+
+        assert_eq!(
+            pair.1
+                .messages()
+                .map(|message| index(&message))
+                .collect::<Vec<_>>(),
+            [0, 1]
+        );
+
+        //nor are the acks in a packet with a bad message behind them
+        let mut ack = pair.1.packet(now).unwrap();
+        ack.push(7);
+
+        assert!(pair.0.receive(&ack, now).is_err());
+        assert_eq!(pair.0.unacked(), 2);
+
+        ack.pop();
+
+        pair.0.receive(&ack, now).unwrap();
+        assert_eq!(pair.0.unacked(), 0);
+    }
+
+    #[test]
+    fn loopback() {
+        let mut sockets = [0, 1].map(|_| Socket::open(SocketType::Datagram).unwrap());
+
+        for socket in &mut sockets {
+            socket.bind("127.0.0.1:0").unwrap();
+        }
+
+        let addresses = sockets
+            .each_ref()
+            .map(|socket| socket.local_addr().unwrap());
+
+        let mut a = Connection::<Vec<u8>>::new(addresses[1]);
+        let mut b = Connection::<Vec<u8>>::new(addresses[0]);
+
+        a.set_loss(0.3);
+        b.set_loss(0.3);
+
+        let count = 200;
+
+        for i in 0..count {
+            a.send(Channel::ReliableOrdered, message(i, 64)).unwrap();
+        }
+
+        let mut received = vec![];
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+
+        while received.len() < count as usize || a.unacked() > 0 {
+            assert!(Instant::now() < deadline, "{} of {}", received.len(), count);
+
+            let now = Instant::now();
+
+            a.update(&sockets[0], now).unwrap();
+            b.update(&sockets[1], now).unwrap();
+
+            let mut recv = sockets[1].recv::<Vec<u8>>();
+
+            while let Poll::Ready(result) = recv.poll() {
+                b.receive(&result.unwrap().0, now).unwrap();
+            }
+
+            let mut recv = sockets[0].recv::<Vec<u8>>();
+
+            while let Poll::Ready(result) = recv.poll() {
+                a.receive(&result.unwrap().0, now).unwrap();
+            }
+
+            received.extend(b.messages().map(|message| index(&message)));
+
+            thread::sleep(Duration::from_millis(1));
+        }
+
+        assert_eq!(received, (0..count).collect::<Vec<_>>());
+    }
+}
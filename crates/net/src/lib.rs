@@ -1,29 +1,177 @@
 #![feature(default_free_fn)]
 
 use std::default::default;
+use std::io;
+use std::marker;
 use std::mem;
 use std::net;
 use std::result;
+use std::task::Poll;
 
 #[cfg(target_os = "windows")]
 use windows::Win32::Networking::WinSock as win_sock;
 
-pub trait Serializable {}
+pub mod connection;
 
-pub trait Deserializable {}
+pub use connection::{Channel, Connection, MAX_MESSAGE_SIZE};
 
-pub struct Send<T> {
-    marker: std::marker::PhantomData<T>,
+//the largest payload a udp datagram can carry
+pub const MAX_DATAGRAM_SIZE: usize = 65507;
+
+pub trait Serializable {
+    fn serialize(&self, buffer: &mut Vec<u8>);
+}
+
+pub trait Deserializable: Sized {
+    fn deserialize(bytes: &[u8]) -> Option<Self>;
+}
+
+impl Serializable for [u8] {
+    fn serialize(&self, buffer: &mut Vec<u8>) {
+        buffer.extend_from_slice(self);
+    }
 }
 
-pub struct Recv<T> {
-    marker: std::marker::PhantomData<T>,
+impl<const N: usize> Serializable for [u8; N] {
+    fn serialize(&self, buffer: &mut Vec<u8>) {
+        buffer.extend_from_slice(self);
+    }
+}
+
+impl Serializable for Vec<u8> {
+    fn serialize(&self, buffer: &mut Vec<u8>) {
+        buffer.extend_from_slice(self);
+    }
+}
+
+impl<T: Serializable + ?Sized> Serializable for &T {
+    fn serialize(&self, buffer: &mut Vec<u8>) {
+        (**self).serialize(buffer);
+    }
+}
+
+impl<const N: usize> Deserializable for [u8; N] {
+    fn deserialize(bytes: &[u8]) -> Option<Self> {
+        bytes.try_into().ok()
+    }
+}
+
+impl Deserializable for Vec<u8> {
+    fn deserialize(bytes: &[u8]) -> Option<Self> {
+        Some(bytes.to_vec())
+    }
+}
+
+//a datagram on its way out, polled until the socket has room for it
+pub struct Send<'a, T> {
+    socket: &'a Socket,
+    address: Option<net::SocketAddr>,
+    bytes: Vec<u8>,
+    sent: Option<usize>,
+    marker: marker::PhantomData<T>,
+}
+
+impl<T> Send<'_, T> {
+    //returns the bytes sent, polling again after that doesn't send twice
+    pub fn poll(&mut self) -> Poll<Result<usize>> {
+        if let Some(sent) = self.sent {
+            return Poll::Ready(Ok(sent));
+        }
+
+        let Some(address) = self.address else {
+            return Poll::Ready(Err(Error::InvalidAddress));
+        };
+
+        match self.socket.send_to(&self.bytes, address) {
+            Err(Error::WouldBlock) => Poll::Pending,
+            Err(error) => Poll::Ready(Err(error)),
+            Ok(sent) => {
+                self.sent = Some(sent);
+                Poll::Ready(Ok(sent))
+            }
+        }
+    }
+
+    //blocks until the datagram is sent
+    pub fn wait(&mut self) -> Result<usize> {
+        loop {
+            if let Poll::Ready(result) = self.poll() {
+                return result;
+            }
+
+            self.socket.wait(Readiness::Write)?;
+        }
+    }
+}
+
+//incoming datagrams, each ready poll takes one off the socket
+pub struct Recv<'a, T> {
+    socket: &'a Socket,
+    buffer: Vec<u8>,
+    marker: marker::PhantomData<T>,
+}
+
+impl<T: Deserializable> Recv<'_, T> {
+    pub fn poll(&mut self) -> Poll<Result<(T, net::SocketAddr)>> {
+        let (received, address) = match self.socket.recv_from(&mut self.buffer) {
+            Err(Error::WouldBlock) => return Poll::Pending,
+            Err(error) => return Poll::Ready(Err(error)),
+            Ok(ok) => ok,
+        };
+
+        let Some(data) = T::deserialize(&self.buffer[..received]) else {
+            return Poll::Ready(Err(Error::InvalidData));
+        };
+
+        Poll::Ready(Ok((data, address)))
+    }
+
+    //blocks until a datagram arrives
+    pub fn wait(&mut self) -> Result<(T, net::SocketAddr)> {
+        loop {
+            if let Poll::Ready(result) = self.poll() {
+                return result;
+            }
+
+            self.socket.wait(Readiness::Read)?;
+        }
+    }
 }
 
 #[derive(Debug)]
 pub enum Error {
     CantOpen,
     AddrAlreadyInUse,
+    InvalidAddress,
+    //the operation would have blocked, try again once the socket is ready
+    WouldBlock,
+    MessageTooLong,
+    ConnectionRefused,
+    //a datagram arrived that couldn't be deserialized
+    InvalidData,
+    //not implemented on this platform yet
+    Unsupported,
+    Io(io::Error),
+}
+
+impl Error {
+    #[cfg(target_os = "linux")]
+    fn last_os_error() -> Self {
+        let error = io::Error::last_os_error();
+
+        match error.raw_os_error() {
+            Some(libc::EAGAIN) => Self::WouldBlock,
+            Some(libc::EMSGSIZE) => Self::MessageTooLong,
+            Some(libc::ECONNREFUSED) => Self::ConnectionRefused,
+            Some(libc::EADDRINUSE) => Self::AddrAlreadyInUse,
+            _ => Self::Io(error),
+        }
+    }
+}
+
+enum Readiness {
+    Read,
+    Write,
 }
 
 type Result<T> = result::Result<T, Error>;
@@ -33,9 +181,14 @@ pub enum SocketType {
     Datagram,
 }
 
+//dual stack on linux, one socket talks to ipv4 and ipv6 peers alike, while on windows sockets
+//are ipv4 only and ipv6 addresses other than v4 mapped ones are refused as InvalidAddress
 pub struct Socket {
     #[cfg(target_os = "linux")]
     handle: libc::c_int,
+    //AF_INET6 for a dual stack socket, AF_INET where ipv6 isn't available
+    #[cfg(target_os = "linux")]
+    family: libc::c_int,
     #[cfg(target_os = "windows")]
     handle: win_sock::SOCKET,
 }
@@ -73,72 +226,470 @@ impl Socket {
     pub fn open(ty: SocketType) -> Result<Self> {
         use libc::*;
 
-        let handle = match ty {
-            SocketType::Stream => unsafe { socket(AF_INET, SOCK_STREAM, IPPROTO_TCP) },
-            SocketType::Datagram => unsafe { socket(AF_INET, SOCK_DGRAM, IPPROTO_UDP) },
+        let (ty, protocol) = match ty {
+            SocketType::Stream => (SOCK_STREAM, IPPROTO_TCP),
+            SocketType::Datagram => (SOCK_DGRAM, IPPROTO_UDP),
         };
 
+        //prefer ipv6 with V6ONLY off, ipv4 then travels as mapped addresses on the same socket
+        let handle = unsafe { socket(AF_INET6, ty, protocol) };
+
+        if handle != -1 {
+            let v6_only: c_int = 0;
+
+            let result = unsafe {
+                setsockopt(
+                    handle,
+                    IPPROTO_IPV6,
+                    IPV6_V6ONLY,
+                    &v6_only as *const _ as *const _,
+                    mem::size_of_val(&v6_only) as _,
+                )
+            };
+
+            if result == 0 {
+                return Ok(Self {
+                    handle,
+                    family: AF_INET6,
+                });
+            }
+
+            unsafe {
+                close(handle);
+            }
+        }
+
+        let handle = unsafe { socket(AF_INET, ty, protocol) };
+
         if handle == -1 {
             Err(Error::CantOpen)?;
         }
 
-        Ok(Self { handle })
+        Ok(Self {
+            handle,
+            family: AF_INET,
+        })
     }
 
     pub fn close(self) {}
 
+    #[cfg(target_os = "linux")]
     pub fn bind(&mut self, addrs: impl net::ToSocketAddrs) -> Result<()> {
-        #[cfg(target_os = "linux")]
-        use libc::*;
+        let Ok(addrs) = addrs.to_socket_addrs() else {
+            Err(Error::InvalidAddress)?
+        };
 
-        #[cfg(target_os = "windows")]
-        use win_sock::*;
+        let mut error = Error::InvalidAddress;
 
-        let mut bound = false;
+        for addr in addrs {
+            let Ok((address, address_len)) = self.encode_address(addr) else {
+                continue;
+            };
+
+            if unsafe { libc::bind(self.handle, &address as *const _ as *const _, address_len) }
+                == 0
+            {
+                return Ok(());
+            }
+
+            error = Error::last_os_error();
+        }
+
+        Err(error)
+    }
 
-        let mut address = [0u8; 512];
-        let mut address_len = 0;
+    //sets the default peer, datagrams from anywhere else are dropped and icmp errors from the
+    //peer surface as ConnectionRefused
+    #[cfg(target_os = "linux")]
+    pub fn connect(&mut self, addrs: impl net::ToSocketAddrs) -> Result<()> {
+        let Ok(addrs) = addrs.to_socket_addrs() else {
+            Err(Error::InvalidAddress)?
+        };
+
+        let mut error = Error::InvalidAddress;
+
+        for addr in addrs {
+            let Ok((address, address_len)) = self.encode_address(addr) else {
+                continue;
+            };
+
+            if unsafe { libc::connect(self.handle, &address as *const _ as *const _, address_len) }
+                == 0
+            {
+                return Ok(());
+            }
 
-        for addr in addrs.to_socket_addrs().unwrap() {
-            match addr {
-                net::SocketAddr::V4(v4addr) => unsafe {
-                    let sin_family = &mut address as *mut u8;
+            error = Error::last_os_error();
+        }
 
-                    *(sin_family as *mut u16) = AF_INET.0 as _;
+        Err(error)
+    }
 
-                    let sin_port = sin_family.add(mem::size_of::<u16>());
+    //a dual stack socket bound to 0.0.0.0 is listening on [::], so that's what is reported
+    #[cfg(target_os = "linux")]
+    pub fn local_addr(&self) -> Result<net::SocketAddr> {
+        let mut address = unsafe { mem::zeroed::<libc::sockaddr_storage>() };
+        let mut address_len = mem::size_of_val(&address) as libc::socklen_t;
+
+        if unsafe {
+            libc::getsockname(
+                self.handle,
+                &mut address as *mut _ as *mut _,
+                &mut address_len,
+            )
+        } == -1
+        {
+            Err(Error::last_os_error())?
+        }
 
-                    *(sin_port as *mut u16) = v4addr.port();
+        decode_address(&address)
+    }
 
-                    let sin_addr = sin_port.add(mem::size_of::<u16>());
+    #[cfg(target_os = "windows")]
+    pub fn bind(&mut self, addrs: impl net::ToSocketAddrs) -> Result<()> {
+        let Ok(addrs) = addrs.to_socket_addrs() else {
+            Err(Error::InvalidAddress)?
+        };
 
-                    *(sin_addr as *mut [u8; 4]) = v4addr.ip().octets();
+        let mut error = Error::InvalidAddress;
 
-                    address_len = 16;
-                },
-                _ => todo!("ipv6 not implemented"),
+        for addr in addrs {
+            let Ok(address) = self.encode_address(addr) else {
+                continue;
+            };
+
+            if unsafe {
+                win_sock::bind(
+                    self.handle,
+                    &address as *const _ as *const _,
+                    mem::size_of_val(&address) as _,
+                )
+            } == 0
+            {
+                return Ok(());
             }
 
-            if unsafe { bind(self.handle, &address as *const _ as *const _, address_len) } == -1 {
+            error = Error::AddrAlreadyInUse;
+        }
+
+        Err(error)
+    }
+
+    #[cfg(target_os = "windows")]
+    pub fn connect(&mut self, addrs: impl net::ToSocketAddrs) -> Result<()> {
+        let Ok(addrs) = addrs.to_socket_addrs() else {
+            Err(Error::InvalidAddress)?
+        };
+
+        let mut error = Error::InvalidAddress;
+
+        for addr in addrs {
+            let Ok(address) = self.encode_address(addr) else {
                 continue;
+            };
+
+            if unsafe {
+                win_sock::connect(
+                    self.handle,
+                    &address as *const _ as *const _,
+                    mem::size_of_val(&address) as _,
+                )
+            } == 0
+            {
+                return Ok(());
             }
 
-            bound = true;
-            break;
+            error = Error::Io(io::Error::last_os_error());
+        }
+
+        Err(error)
+    }
+
+    #[cfg(target_os = "windows")]
+    pub fn local_addr(&self) -> Result<net::SocketAddr> {
+        let mut address = SockaddrIn::default();
+        let mut address_len = mem::size_of_val(&address) as i32;
+
+        if unsafe {
+            win_sock::getsockname(
+                self.handle,
+                &mut address as *mut _ as *mut _,
+                &mut address_len,
+            )
+        } != 0
+        {
+            Err(Error::Io(io::Error::last_os_error()))?
+        }
+
+        if address.family != win_sock::AF_INET.0 as u16 {
+            Err(Error::InvalidAddress)?
+        }
+
+        let ip = net::Ipv4Addr::from(address.addr);
+        let port = u16::from_be(address.port);
+
+        Ok(net::SocketAddrV4::new(ip, port).into())
+    }
+
+    //nothing is sent until the handle is polled
+    pub fn send<T: Serializable>(&self, address: impl net::ToSocketAddrs, data: T) -> Send<'_, T> {
+        //the first address this socket's family can reach
+        let address = address
+            .to_socket_addrs()
+            .ok()
+            .and_then(|mut addrs| addrs.find(|&address| self.encode_address(address).is_ok()));
+
+        let mut bytes = vec![];
+
+        data.serialize(&mut bytes);
+
+        Send {
+            socket: self,
+            address,
+            bytes,
+            sent: None,
+            marker: default(),
         }
+    }
 
-        if bound {
-            Ok(())
-        } else {
-            Err(Error::AddrAlreadyInUse)
+    //sends without waiting, a full send buffer drops the datagram the same as the network
+    //losing it, which is fine for protocols that already cope with loss
+    pub fn send_lossy<T: Serializable>(
+        &self,
+        address: impl net::ToSocketAddrs,
+        data: T,
+    ) -> Result<()> {
+        match self.send(address, data).poll() {
+            Poll::Ready(Err(error)) => Err(error),
+            _ => Ok(()),
         }
     }
 
-    pub fn send<T: Serializable>(&mut self, address: impl net::ToSocketAddrs, data: T) -> Send<T> {
-        todo!()
+    pub fn recv<T: Deserializable>(&self) -> Recv<'_, T> {
+        Recv {
+            socket: self,
+            //one byte over the limit so oversized datagrams are caught rather than cut short
+            buffer: vec![0; MAX_DATAGRAM_SIZE + 1],
+            marker: default(),
+        }
     }
-    pub fn recv<T: Deserializable>(&mut self) -> Recv<T> {
-        todo!()
+
+    #[cfg(target_os = "linux")]
+    fn send_to(&self, bytes: &[u8], address: net::SocketAddr) -> Result<usize> {
+        let (address, address_len) = self.encode_address(address)?;
+
+        let sent = unsafe {
+            libc::sendto(
+                self.handle,
+                bytes.as_ptr() as *const _,
+                bytes.len(),
+                libc::MSG_DONTWAIT,
+                &address as *const _ as *const _,
+                address_len,
+            )
+        };
+
+        if sent == -1 {
+            Err(Error::last_os_error())?
+        }
+
+        Ok(sent as usize)
+    }
+
+    #[cfg(target_os = "linux")]
+    fn recv_from(&self, buffer: &mut [u8]) -> Result<(usize, net::SocketAddr)> {
+        let mut address = unsafe { mem::zeroed::<libc::sockaddr_storage>() };
+        let mut address_len = mem::size_of_val(&address) as libc::socklen_t;
+
+        //with MSG_TRUNC the full length is returned even if it didn't fit
+        let received = unsafe {
+            libc::recvfrom(
+                self.handle,
+                buffer.as_mut_ptr() as *mut _,
+                buffer.len(),
+                libc::MSG_DONTWAIT | libc::MSG_TRUNC,
+                &mut address as *mut _ as *mut _,
+                &mut address_len,
+            )
+        };
+
+        if received == -1 {
+            Err(Error::last_os_error())?
+        }
+
+        if received as usize > MAX_DATAGRAM_SIZE {
+            Err(Error::MessageTooLong)?
+        }
+
+        Ok((received as usize, decode_address(&address)?))
+    }
+
+    //converts to the socket's family, ipv4 is mapped into ipv6 on a dual stack socket
+    #[cfg(target_os = "linux")]
+    fn encode_address(
+        &self,
+        address: net::SocketAddr,
+    ) -> Result<(libc::sockaddr_storage, libc::socklen_t)> {
+        use libc::*;
+
+        let address = match (self.family, address) {
+            (AF_INET6, net::SocketAddr::V4(address)) => {
+                //binding the mapped 0.0.0.0 would only listen on ipv4
+                let ip = if address.ip().is_unspecified() {
+                    net::Ipv6Addr::UNSPECIFIED
+                } else {
+                    address.ip().to_ipv6_mapped()
+                };
+
+                net::SocketAddrV6::new(ip, address.port(), 0, 0).into()
+            }
+            (AF_INET, net::SocketAddr::V6(address)) => {
+                let Some(ip) = address.ip().to_ipv4_mapped() else {
+                    Err(Error::InvalidAddress)?
+                };
+
+                net::SocketAddrV4::new(ip, address.port()).into()
+            }
+            (_, address) => address,
+        };
+
+        let mut storage = unsafe { mem::zeroed::<sockaddr_storage>() };
+
+        let len = match address {
+            net::SocketAddr::V4(address) => {
+                let storage = unsafe { &mut *(&mut storage as *mut _ as *mut sockaddr_in) };
+
+                storage.sin_family = AF_INET as _;
+                storage.sin_port = address.port().to_be();
+                storage.sin_addr = in_addr {
+                    s_addr: u32::from_ne_bytes(address.ip().octets()),
+                };
+
+                mem::size_of::<sockaddr_in>()
+            }
+            net::SocketAddr::V6(address) => {
+                let storage = unsafe { &mut *(&mut storage as *mut _ as *mut sockaddr_in6) };
+
+                storage.sin6_family = AF_INET6 as _;
+                storage.sin6_port = address.port().to_be();
+                storage.sin6_flowinfo = address.flowinfo();
+                storage.sin6_addr = in6_addr {
+                    s6_addr: address.ip().octets(),
+                };
+                storage.sin6_scope_id = address.scope_id();
+
+                mem::size_of::<sockaddr_in6>()
+            }
+        };
+
+        Ok((storage, len as socklen_t))
+    }
+
+    #[cfg(target_os = "linux")]
+    fn wait(&self, readiness: Readiness) -> Result<()> {
+        let events = match readiness {
+            Readiness::Read => libc::POLLIN,
+            Readiness::Write => libc::POLLOUT,
+        };
+
+        let mut fd = libc::pollfd {
+            fd: self.handle,
+            events,
+            revents: 0,
+        };
+
+        if unsafe { libc::poll(&mut fd, 1, -1) } == -1 {
+            let error = io::Error::last_os_error();
+
+            //interrupted by a signal, the caller polls again anyway
+            if error.kind() != io::ErrorKind::Interrupted {
+                Err(Error::Io(error))?
+            }
+        }
+
+        Ok(())
+    }
+
+    //sockets are ipv4 only here, so ipv6 addresses other than mapped ones can't be reached
+    #[cfg(target_os = "windows")]
+    fn encode_address(&self, address: net::SocketAddr) -> Result<SockaddrIn> {
+        let address = match address {
+            net::SocketAddr::V4(address) => address,
+            net::SocketAddr::V6(address) => {
+                let Some(ip) = address.ip().to_ipv4_mapped() else {
+                    Err(Error::InvalidAddress)?
+                };
+
+                net::SocketAddrV4::new(ip, address.port())
+            }
+        };
+
+        Ok(SockaddrIn {
+            family: win_sock::AF_INET.0 as u16,
+            port: address.port().to_be(),
+            addr: address.ip().octets(),
+            zero: [0; 8],
+        })
+    }
+
+    #[cfg(target_os = "windows")]
+    fn send_to(&self, _bytes: &[u8], _address: net::SocketAddr) -> Result<usize> {
+        Err(Error::Unsupported)
+    }
+
+    #[cfg(target_os = "windows")]
+    fn recv_from(&self, _buffer: &mut [u8]) -> Result<(usize, net::SocketAddr)> {
+        Err(Error::Unsupported)
+    }
+
+    #[cfg(target_os = "windows")]
+    fn wait(&self, _readiness: Readiness) -> Result<()> {
+        Err(Error::Unsupported)
+    }
+}
+
+//sockaddr_in, laid out by hand so it doesn't depend on how the bindings name its unions
+#[cfg(target_os = "windows")]
+#[derive(Default)]
+#[repr(C)]
+struct SockaddrIn {
+    family: u16,
+    //network byte order
+    port: u16,
+    addr: [u8; 4],
+    zero: [u8; 8],
+}
+
+#[cfg(target_os = "linux")]
+fn decode_address(address: &libc::sockaddr_storage) -> Result<net::SocketAddr> {
+    use libc::*;
+
+    match address.ss_family as c_int {
+        AF_INET => {
+            let address = unsafe { &*(address as *const _ as *const sockaddr_in) };
+
+            let ip = net::Ipv4Addr::from(address.sin_addr.s_addr.to_ne_bytes());
+            let port = u16::from_be(address.sin_port);
+
+            Ok(net::SocketAddrV4::new(ip, port).into())
+        }
+        AF_INET6 => {
+            let address = unsafe { &*(address as *const _ as *const sockaddr_in6) };
+
+            let ip = net::Ipv6Addr::from(address.sin6_addr.s6_addr);
+            let port = u16::from_be(address.sin6_port);
+
+            //ipv4 peers of a dual stack socket show up as mapped addresses
+            if let Some(ip) = ip.to_ipv4_mapped() {
+                return Ok(net::SocketAddrV4::new(ip, port).into());
+            }
+
+            Ok(
+                net::SocketAddrV6::new(ip, port, address.sin6_flowinfo, address.sin6_scope_id)
+                    .into(),
+            )
+        }
+        _ => Err(Error::InvalidAddress),
     }
 }
 
@@ -155,3 +706,85 @@ impl Drop for Socket {
         }
     }
 }
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    fn bound(address: &str) -> Socket {
+        let mut socket = Socket::open(SocketType::Datagram).unwrap();
+
+        socket.bind(address).unwrap();
+
+        socket
+    }
+
+    #[test]
+    fn local_addr_round_trips() {
+        for address in ["127.0.0.1:0", "[::1]:0", "0.0.0.0:0", "[::]:0"] {
+            let socket = bound(address);
+            let local = socket.local_addr().unwrap();
+
+            assert_ne!(local.port(), 0, "{}", address);
+
+            let requested: net::SocketAddr = address.parse().unwrap();
+
+            match requested.ip() {
+                ip if ip.is_unspecified() => assert!(local.ip().is_unspecified(), "{}", address),
+                ip => assert_eq!(local.ip(), ip),
+            }
+
+            //binding the reported address again collides with the socket already there
+            assert!(Socket::open(SocketType::Datagram)
+                .unwrap()
+                .bind(local)
+                .is_err());
+        }
+    }
+
+    #[test]
+    fn unspecified_v4_binds_both_stacks() {
+        let socket = bound("0.0.0.0:0");
+
+        if socket.family == libc::AF_INET6 {
+            let local = socket.local_addr().unwrap();
+
+            assert_eq!(local.ip(), net::IpAddr::V6(net::Ipv6Addr::UNSPECIFIED));
+        }
+    }
+
+    #[test]
+    fn dual_stack_receives_v4_and_v6() {
+        let receiver = bound("[::]:0");
+        let port = receiver.local_addr().unwrap().port();
+
+        for (from, to) in [("127.0.0.1:0", "127.0.0.1"), ("[::1]:0", "::1")] {
+            let sender = bound(from);
+            let sender_address = sender.local_addr().unwrap();
+
+            let to: net::IpAddr = to.parse().unwrap();
+
+            sender.send((to, port), [1u8, 2, 3]).wait().unwrap();
+
+            let (data, address) = receiver.recv::<[u8; 3]>().wait().unwrap();
+
+            assert_eq!(data, [1, 2, 3]);
+            assert_eq!(address, sender_address);
+        }
+    }
+
+    #[test]
+    fn connect_to_localhost() {
+        let receiver = bound("[::]:0");
+        let port = receiver.local_addr().unwrap().port();
+
+        let mut sender = bound("127.0.0.1:0");
+
+        sender.connect(format!("localhost:{}", port)).unwrap();
+        sender.send(("localhost", port), [7u8]).wait().unwrap();
+
+        let (data, _) = receiver.recv::<[u8; 1]>().wait().unwrap();
+
+        assert_eq!(data, [7]);
+    }
+}
@@ -1,5 +1,18 @@
+use math::geometry::Aabb;
 use math::prelude::*;
-struct Collider {
-    start: Vector<i32, 3>,
-    end: Vector<i32, 3>,
+
+//a box relative to its entity's position
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Collider {
+    pub start: Vector<f32, 3>,
+    pub end: Vector<f32, 3>,
+}
+
+impl Collider {
+    pub fn aabb(&self, position: Vector<f32, 3>) -> Aabb<f32> {
+        Aabb {
+            start: position + self.start,
+            end: position + self.end,
+        }
+    }
 }
@@ -1,3 +1,6 @@
-mod collider;
-mod rigidbody;
+pub mod collider;
+pub mod rigidbody;
 mod transform;
+
+pub use collider::Collider;
+pub use rigidbody::Rigidbody;
@@ -1,8 +1,246 @@
+use crate::collider::Collider;
+
+use math::geometry::Aabb;
 use math::prelude::*;
-struct Rigidbody {
-    linear_velocity: Vector<f32, 3>,
-    linear_acceleration: Vector<f32, 3>,
-    angular_velocity: Vector<f32, 3>,
-    angular_acceleration: Vector<f32, 3>,
-    mass: f32,
+
+//the furthest a body moves before checking for collisions, under a voxel so nothing tunnels
+const MAX_STEP: f32 = 0.5;
+//the gap left between a body and what it hit, so resting contact doesn't count as overlap
+const SKIN: f32 = 1e-3;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Rigidbody {
+    pub linear_velocity: Vector<f32, 3>,
+    pub linear_acceleration: Vector<f32, 3>,
+    pub angular_velocity: Vector<f32, 3>,
+    pub angular_acceleration: Vector<f32, 3>,
+    pub mass: f32,
+    //whether the last step ended standing on something
+    pub on_ground: bool,
+}
+
+impl Rigidbody {
+    //integrates over `delta` seconds, moving along one axis at a time and stopping at the unit
+    //voxels `solid` reports, so bodies slide along walls and floors
+    //angular motion isn't simulated yet
+    pub fn step(
+        &mut self,
+        position: &mut Vector<f32, 3>,
+        collider: &Collider,
+        delta: f32,
+        solid: impl Fn(Vector<i32, 3>) -> bool,
+    ) {
+        self.linear_velocity += self.linear_acceleration * delta;
+        self.on_ground = false;
+
+        //vertical first, so a landing is resolved before sliding along the floor
+        for axis in [1, 0, 2] {
+            let mut remaining = self.linear_velocity[axis] * delta;
+
+            //nan never reaches zero and infinity never shrinks, either would loop forever
+            if !remaining.is_finite() {
+                self.linear_velocity[axis] = 0.0;
+
+                continue;
+            }
+
+            while remaining != 0.0 {
+                let step = remaining.clamp(-MAX_STEP, MAX_STEP);
+
+                remaining -= step;
+                position[axis] += step;
+
+                let Some(correction) = penetration(collider.aabb(*position), axis, step, &solid)
+                else {
+                    continue;
+                };
+
+                position[axis] += correction;
+
+                if axis == 1 && step < 0.0 {
+                    self.on_ground = true;
+                }
+
+                self.linear_velocity[axis] = 0.0;
+
+                break;
+            }
+        }
+    }
+}
+
+//how far back along `axis` the box has to move to leave the solid voxels it entered by
+//moving `step`
+fn penetration(
+    aabb: Aabb<f32>,
+    axis: usize,
+    step: f32,
+    solid: &impl Fn(Vector<i32, 3>) -> bool,
+) -> Option<f32> {
+    let start = aabb.start.floor().castf::<i32>();
+    let end = aabb.end.ceil().castf::<i32>();
+
+    //the nearest face of a solid voxel the box moved into
+    let mut nearest = None::<f32>;
+
+    for x in start[0]..end[0] {
+        for y in start[1]..end[1] {
+            for z in start[2]..end[2] {
+                let voxel = Vector::new([x, y, z]);
+
+                if !solid(voxel) {
+                    continue;
+                }
+
+                let face = if step > 0.0 {
+                    voxel[axis] as f32
+                } else {
+                    voxel[axis] as f32 + 1.0
+                };
+
+                nearest = Some(match nearest {
+                    Some(nearest) if step > 0.0 => nearest.min(face),
+                    Some(nearest) => nearest.max(face),
+                    None => face,
+                });
+            }
+        }
+    }
+
+    let face = nearest?;
+
+    if step > 0.0 {
+        Some(face - aabb.end[axis] - SKIN)
+    } else {
+        Some(face - aabb.start[axis] + SKIN)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DELTA: f32 = 1.0 / 60.0;
+    const GRAVITY: f32 = -20.0;
+
+    fn collider() -> Collider {
+        Collider {
+            start: Vector::new([-0.4, -1.8, -0.4]),
+            end: Vector::new([0.4, 0.1, 0.4]),
+        }
+    }
+
+    fn falling(velocity: [f32; 3]) -> Rigidbody {
+        Rigidbody {
+            linear_velocity: Vector::new(velocity),
+            linear_acceleration: Vector::new([0.0, GRAVITY, 0.0]),
+            mass: 1.0,
+            ..Default::default()
+        }
+    }
+
+    fn floor(voxel: Vector<i32, 3>) -> bool {
+        voxel[1] < 0
+    }
+
+    //the feet rest just above y = 0
+    const STANDING: f32 = 1.8 + SKIN;
+
+    #[test]
+    fn lands_on_floor() {
+        let mut body = falling([0.0; 3]);
+        let mut position = Vector::new([0.5, 3.0, 0.5]);
+
+        for _ in 0..120 {
+            body.step(&mut position, &collider(), DELTA, floor);
+        }
+
+        assert!(body.on_ground);
+        assert_eq!(body.linear_velocity[1], 0.0);
+        assert!((position[1] - STANDING).abs() < 1e-4, "{}", position[1]);
+        assert_eq!((position[0], position[2]), (0.5, 0.5));
+    }
+
+    #[test]
+    fn slides_along_wall() {
+        let wall = |voxel: Vector<i32, 3>| floor(voxel) || voxel[0] >= 3;
+
+        let mut body = falling([5.0, 0.0, 2.0]);
+        let mut position = Vector::new([0.5, STANDING, 0.5]);
+
+        for _ in 0..60 {
+            //keeps pushing into the wall, like a player holding a key
+            body.linear_velocity[0] = 5.0;
+            body.linear_velocity[2] = 2.0;
+
+            body.step(&mut position, &collider(), DELTA, wall);
+        }
+
+        //stopped at the wall but kept moving along it
+        assert!(position[0] + 0.4 <= 3.0 && position[0] + 0.4 > 3.0 - 0.01);
+        assert!((position[2] - 2.5).abs() < 1e-3, "{}", position[2]);
+        assert!((position[1] - STANDING).abs() < 1e-4);
+        assert!(body.on_ground);
+    }
+
+    #[test]
+    fn no_tunnelling() {
+        //a floor one voxel thick, far less than a step's travel
+        let thin = |voxel: Vector<i32, 3>| voxel[1] == 0;
+
+        let mut body = falling([0.0, -1000.0, 0.0]);
+        let mut position = Vector::new([0.5, 50.0, 0.5]);
+
+        body.step(&mut position, &collider(), 0.1, thin);
+
+        assert!(body.on_ground);
+        assert!(
+            (position[1] - (1.0 + STANDING)).abs() < 1e-4,
+            "{}",
+            position[1]
+        );
+    }
+
+    #[test]
+    fn jump_leaves_and_returns_to_ground() {
+        let mut body = falling([0.0; 3]);
+        let mut position = Vector::new([0.5, STANDING, 0.5]);
+
+        body.step(&mut position, &collider(), DELTA, floor);
+
+        assert!(body.on_ground);
+
+        body.linear_velocity[1] = 8.0;
+
+        let mut airborne = 0;
+
+        loop {
+            body.step(&mut position, &collider(), DELTA, floor);
+
+            if body.on_ground {
+                break;
+            }
+
+            airborne += 1;
+
+            assert!(airborne < 120, "never landed");
+        }
+
+        //8 m/s against 20 m/s^2 is 0.8s in the air
+        assert!((45..=50).contains(&airborne), "{}", airborne);
+        assert!((position[1] - STANDING).abs() < 1e-4);
+    }
+
+    #[test]
+    fn non_finite_velocity_stops() {
+        for velocity in [f32::NAN, f32::INFINITY, f32::NEG_INFINITY] {
+            let mut body = falling([velocity, velocity, 0.0]);
+            let mut position = Vector::new([0.5, 3.0, 0.5]);
+
+            body.step(&mut position, &collider(), DELTA, floor);
+
+            assert_eq!(body.linear_velocity, Vector::new([0.0; 3]));
+            assert_eq!(position, Vector::new([0.5, 3.0, 0.5]));
+        }
+    }
 }
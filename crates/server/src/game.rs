@@ -0,0 +1,178 @@
+use common::block::Block;
+use common::octree::*;
+use common::voxel::*;
+
+use math::prelude::*;
+
+use physics::{Collider, Rigidbody};
+
+use std::collections::HashMap;
+
+const GRAVITY: f32 = -40.0;
+//speeds from input.glsl, walking is 1.3 times its human factor
+const WALK_SPEED: f32 = 1.3 * 7.3;
+const JUMP_SPEED: f32 = 13.0;
+//how quickly lateral velocity follows the input, the same smoothing as input.glsl
+const MOVE_RATE: f32 = 2.0;
+//entities that fall out of the world start over
+const KILL_DEPTH: f32 = -64.0;
+
+pub struct Entity {
+    pub position: Vector<f32, 3>,
+    rigidbody: Rigidbody,
+    direction: Vector<f32, 2>,
+    jump: bool,
+}
+
+//the authoritative state, advanced in fixed steps
+pub struct Game {
+    world: SparseOctree<Voxel>,
+    entities: HashMap<usize, Entity>,
+    spawn: Vector<f32, 3>,
+}
+
+impl Game {
+    pub fn new(world: SparseOctree<Voxel>) -> Self {
+        let spawn = spawn_point(&world);
+
+        Self {
+            world,
+            entities: HashMap::new(),
+            spawn,
+        }
+    }
+
+    pub fn entities(&self) -> impl Iterator<Item = (usize, &Entity)> {
+        self.entities.iter().map(|(&id, entity)| (id, entity))
+    }
+
+    pub fn spawn(&mut self, id: usize) -> Vector<f32, 3> {
+        self.entities.insert(
+            id,
+            Entity {
+                position: self.spawn,
+                rigidbody: Rigidbody {
+                    linear_acceleration: Vector::new([0.0, GRAVITY, 0.0]),
+                    mass: 1.0,
+                    ..Default::default()
+                },
+                direction: Vector::new([0.0, 0.0]),
+                jump: false,
+            },
+        );
+
+        self.spawn
+    }
+
+    pub fn despawn(&mut self, id: usize) {
+        self.entities.remove(&id);
+    }
+
+    //the latest input is kept until the next one arrives
+    pub fn input(&mut self, id: usize, direction: Vector<f32, 2>, jump: bool) {
+        let Some(entity) = self.entities.get_mut(&id) else {
+            return;
+        };
+
+        let length = direction.magnitude();
+
+        //clients don't get to walk faster than anyone else
+        entity.direction = if length.is_finite() && length > 1.0 {
+            direction / length
+        } else if length.is_finite() {
+            direction
+        } else {
+            Vector::new([0.0, 0.0])
+        };
+
+        entity.jump = jump;
+    }
+
+    pub fn step(&mut self, delta: f32) {
+        let collider = player_collider();
+
+        let solid = |position: Vector<i32, 3>| is_solid(&self.world, position);
+
+        for entity in self.entities.values_mut() {
+            let target = entity.direction * WALK_SPEED;
+            let t = (-MOVE_RATE * delta).exp2();
+
+            let velocity = &mut entity.rigidbody.linear_velocity;
+
+            velocity[0] = velocity[0] * (1.0 - t) + target[0] * t;
+            velocity[2] = velocity[2] * (1.0 - t) + target[1] * t;
+
+            if entity.jump && entity.rigidbody.on_ground {
+                velocity[1] = JUMP_SPEED;
+            }
+
+            entity
+                .rigidbody
+                .step(&mut entity.position, &collider, delta, solid);
+
+            if entity.position[1] < KILL_DEPTH {
+                entity.position = self.spawn;
+                entity.rigidbody.linear_velocity = Vector::new([0.0; 3]);
+            }
+        }
+    }
+}
+
+//a flat world of stone under dirt and grass, for when no world is given
+pub fn flat_world() -> SparseOctree<Voxel> {
+    let mut world = SparseOctree::with_depth(8).unwrap();
+
+    let extent = world.extent();
+
+    let layers = [("stone", 0, 60), ("dirt", 60, 63), ("grass", 63, 64)];
+
+    for (name, bottom, top) in layers {
+        let voxel = Voxel {
+            id: Block::by_name(name).unwrap().id,
+        };
+
+        world
+            .fill_box(
+                Vector::new([0, bottom, 0]),
+                Vector::new([extent, top, extent]),
+                voxel,
+            )
+            .unwrap();
+    }
+
+    world
+}
+
+//the eye, 1.8 above the feet, with the same box as physics.glsl
+fn player_collider() -> Collider {
+    Collider {
+        start: Vector::new([-0.4, -1.8, -0.4]),
+        end: Vector::new([0.4, 0.1, 0.4]),
+    }
+}
+
+fn is_solid(world: &SparseOctree<Voxel>, position: Vector<i32, 3>) -> bool {
+    if position.iter().any(|&x| x < 0) {
+        return false;
+    }
+
+    let position = Vector::new(position.map(|x| x as usize));
+
+    world.query(position).is_some_and(Voxel::is_solid)
+}
+
+//standing on the highest solid voxel in the middle of the world
+fn spawn_point(world: &SparseOctree<Voxel>) -> Vector<f32, 3> {
+    let center = world.extent() as i32 / 2;
+
+    let ground = (0..world.extent() as i32)
+        .rev()
+        .find(|&y| is_solid(world, Vector::new([center, y, center])))
+        .map_or(0, |y| y + 1);
+
+    Vector::new([
+        center as f32 + 0.5,
+        ground as f32 + 1.81,
+        center as f32 + 0.5,
+    ])
+}
@@ -1,11 +1,167 @@
+mod game;
+mod session;
+
+use common::net::*;
+use common::octree::SparseOctree;
+
+use game::*;
+
+use net::{Socket, SocketType};
+
+use session::*;
+
+use std::env;
+use std::fs;
+use std::io;
+use std::task::Poll;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const ADDRESS: &str = "0.0.0.0:29753";
+const DEFAULT_TICK_RATE: u32 = 30;
+
+//server [--tick-rate hz] [--world path]
 fn main() {
     println!("Hello, server!");
 
-    use net::*;
+    let mut tick_rate = DEFAULT_TICK_RATE;
+    let mut world_path = None;
+
+    let mut args = env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--tick-rate" => {
+                tick_rate = args
+                    .next()
+                    .and_then(|rate| rate.parse().ok())
+                    .filter(|&rate| rate > 0)
+                    .expect("--tick-rate takes a positive whole number")
+            }
+            "--world" => world_path = Some(args.next().expect("--world takes a path")),
+            _ => panic!("unknown argument {}", arg),
+        }
+    }
+
+    let world = match world_path {
+        Some(path) => {
+            let file = fs::File::open(path).expect("failed to open the world");
+
+            SparseOctree::load(&mut io::BufReader::new(file)).expect("failed to load the world")
+        }
+        None => flat_world(),
+    };
 
     let mut socket = Socket::open(SocketType::Datagram).unwrap();
 
-    socket.bind("0.0.0.0:29753").unwrap();
+    socket.bind(ADDRESS).unwrap();
+
+    let mut recv = socket.recv::<Packet>();
+
+    let mut sessions = Sessions::new();
+    let mut game = Game::new(world);
+
+    let tick = Duration::from_secs(1) / tick_rate;
+    let mut next_tick = Instant::now();
+
+    for tick_count in 0u64.. {
+        next_tick += tick;
+
+        let now = Instant::now();
+
+        while let Poll::Ready(result) = recv.poll() {
+            match result {
+                Ok((packet, address)) => {
+                    if let Some(event) = sessions.receive(&socket, packet, address, now) {
+                        handle(event, &mut game, &mut sessions);
+                    }
+                }
+                Err(error) => println!("failed to receive: {:?}", error),
+            }
+        }
+
+        for session in sessions.iter_mut() {
+            for message in session.connection.messages() {
+                //everything else is the server's to say
+                if let Message::Input { direction, jump } = message {
+                    game.input(session.id, direction, jump);
+                }
+            }
+        }
+
+        game.step(tick.as_secs_f32());
+
+        let snapshot = game
+            .entities()
+            .map(|(id, entity)| Message::Move {
+                id,
+                position: entity.position,
+            })
+            .collect::<Vec<_>>();
+
+        for session in sessions.iter_mut() {
+            for &message in &snapshot {
+                session.send(message);
+            }
+        }
+
+        for event in sessions.update(&socket, now) {
+            handle(event, &mut game, &mut sessions);
+        }
+
+        let now = Instant::now();
+
+        if now > next_tick {
+            println!("tick {} overran by {:?}", tick_count, now - next_tick);
+
+            //start over from here rather than running ticks back to back to catch up
+            next_tick = now;
+        } else {
+            thread::sleep(next_tick - now);
+        }
+    }
+}
+
+//spawns and despawns entities for sessions coming and going and tells everyone
+fn handle(event: Event, game: &mut Game, sessions: &mut Sessions) {
+    let (id, message) = match event {
+        Event::Connected { id, address } => {
+            println!("{} connected from {}", id, address);
+
+            let position = game.spawn(id);
+
+            (id, Message::Spawn { id, position })
+        }
+        Event::Disconnected { id } => {
+            println!("{} disconnected", id);
+
+            game.despawn(id);
+
+            (id, Message::Despawn { id })
+        }
+        Event::TimedOut { id } => {
+            println!("{} timed out", id);
+
+            game.despawn(id);
+
+            (id, Message::Despawn { id })
+        }
+    };
+
+    for session in sessions.iter_mut() {
+        if session.id != id {
+            session.send(message);
+            continue;
+        }
+
+        //a newcomer hears about everyone, itself included
+        for (id, entity) in game.entities() {
+            let message = Message::Spawn {
+                id,
+                position: entity.position,
+            };
 
-    loop {}
+            session.send(message);
+        }
+    }
 }
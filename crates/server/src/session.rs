@@ -0,0 +1,192 @@
+use common::net::*;
+
+use net::Socket;
+
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+use std::net::SocketAddr;
+use std::time::Instant;
+
+const MAX_SESSIONS: usize = 64;
+
+//a connected client
+pub struct Session {
+    pub id: usize,
+    pub address: SocketAddr,
+    pub connection: Connection,
+    last_received: Instant,
+    last_sent: Instant,
+}
+
+impl Session {
+    //queues a message on the channel it belongs on
+    pub fn send(&mut self, message: Message) {
+        if let Err(error) = self.connection.send(message.channel(), message) {
+            println!("failed to queue {:?} for {}: {:?}", message, self.id, error);
+        }
+    }
+}
+
+pub enum Event {
+    Connected { id: usize, address: SocketAddr },
+    Disconnected { id: usize },
+    TimedOut { id: usize },
+}
+
+//everyone who finished the handshake, keyed by address
+pub struct Sessions {
+    sessions: HashMap<SocketAddr, Session>,
+    next_id: usize,
+    //keys the challenge tokens, so clients halfway through the handshake need no state
+    secret: RandomState,
+}
+
+impl Sessions {
+    pub fn new() -> Self {
+        Self {
+            sessions: HashMap::new(),
+            next_id: 0,
+            secret: RandomState::new(),
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut Session> {
+        self.sessions.values_mut()
+    }
+
+    //send failures are logged per peer rather than returned, so the event for a change
+    //already made to the table is never lost
+    pub fn receive(
+        &mut self,
+        socket: &Socket,
+        packet: Packet,
+        address: SocketAddr,
+        now: Instant,
+    ) -> Option<Event> {
+        if let Some(session) = self.sessions.get_mut(&address) {
+            session.last_received = now;
+
+            match packet {
+                //a client sending garbage is kicked
+                Packet::Payload(bytes) if session.connection.receive(&bytes, now).is_err() => {
+                    let session = self.sessions.remove(&address).unwrap();
+
+                    send(socket, address, Packet::Disconnect);
+
+                    return Some(Event::Disconnected { id: session.id });
+                }
+                //the accept was lost, the client is still shaking hands
+                Packet::Request { .. } | Packet::Response { .. } => {
+                    send(socket, address, Packet::Accept { id: session.id })
+                }
+                Packet::Disconnect => {
+                    let session = self.sessions.remove(&address).unwrap();
+
+                    return Some(Event::Disconnected { id: session.id });
+                }
+                _ => {}
+            }
+
+            return None;
+        }
+
+        match packet {
+            Packet::Request { version } if version != PROTOCOL_VERSION => send(
+                socket,
+                address,
+                Packet::Deny {
+                    reason: Deny::VersionMismatch,
+                },
+            ),
+            Packet::Request { .. } => send(
+                socket,
+                address,
+                Packet::Challenge {
+                    token: self.token(address),
+                },
+            ),
+            Packet::Response { token } if token == self.token(address) => {
+                if self.sessions.len() >= MAX_SESSIONS {
+                    send(
+                        socket,
+                        address,
+                        Packet::Deny {
+                            reason: Deny::ServerFull,
+                        },
+                    );
+
+                    return None;
+                }
+
+                let id = self.next_id;
+
+                self.next_id += 1;
+
+                self.sessions.insert(
+                    address,
+                    Session {
+                        id,
+                        address,
+                        connection: Connection::new(address),
+                        last_received: now,
+                        last_sent: now,
+                    },
+                );
+
+                //a lost accept is sent again when the client retries
+                send(socket, address, Packet::Accept { id });
+
+                return Some(Event::Connected { id, address });
+            }
+            _ => {}
+        }
+
+        None
+    }
+
+    //sends what each connection has queued, heartbeats to quiet ones, and drops the ones
+    //that have gone quiet for too long
+    pub fn update(&mut self, socket: &Socket, now: Instant) -> Vec<Event> {
+        let mut events = vec![];
+
+        self.sessions.retain(|_, session| {
+            let alive = now.duration_since(session.last_received) < TIMEOUT;
+
+            if !alive {
+                events.push(Event::TimedOut { id: session.id });
+            }
+
+            alive
+        });
+
+        for session in self.sessions.values_mut() {
+            //as many packets as it takes, so big snapshots don't back up
+            while let Some(bytes) = session.connection.packet(now) {
+                session.last_sent = now;
+
+                send(socket, session.address, Packet::Payload(bytes));
+            }
+
+            if now.duration_since(session.last_sent) >= HEARTBEAT_INTERVAL {
+                session.last_sent = now;
+
+                send(socket, session.address, Packet::Heartbeat);
+            }
+        }
+
+        events
+    }
+
+    fn token(&self, address: SocketAddr) -> u64 {
+        self.secret.hash_one(address)
+    }
+}
+
+//one peer failing doesn't hold up the others, what was lost is resent by the connection or the
+//handshake the same as a lost packet
+fn send(socket: &Socket, address: SocketAddr, packet: Packet) {
+    if let Err(error) = socket.send_lossy(address, packet) {
+        println!("failed to send to {}: {:?}", address, error);
+    }
+}
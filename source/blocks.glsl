@@ -1,15 +1,15 @@
 #define HASH_START 2166136261
-#define BLOCK_ID_VOID 0
-#define BLOCK_ID_AIR 1
+//BLOCK_* defines are generated from assets/blocks.toml
+const bool block_solid[BLOCK_COUNT] = BLOCK_SOLID_TABLE;
+const vec3 block_from[BLOCK_COUNT] = BLOCK_FROM_TABLE;
+const vec3 block_to[BLOCK_COUNT] = BLOCK_TO_TABLE;
 
 bool is_solid(u16 id) {
-	switch(u32(id)) {
-		case BLOCK_ID_AIR:
-			return false;
+	if(u32(id) >= BLOCK_COUNT) {
+		return true;
 	}
 
-	return true;
-
+	return block_solid[u32(id)];
 }
 
 uint tumble_fnv(uint hash, uint data) {
@@ -49,7 +49,7 @@ void main() {
 
 			voxel_query(query);
 
-			if(query.id == u16(2) && visited
+			if(query.id == u16(BLOCK_ID_GRASS) && visited
 					[start.x]
 					[start.y]
 					[start.z]
@@ -95,7 +95,7 @@ void main() {
 		
 				voxel_query(query);
 
-				if(query.id != u16(2) || visited
+				if(query.id != u16(BLOCK_ID_GRASS) || visited
 					[probe.x]
 					[probe.y]
 					[probe.z]
@@ -121,7 +121,7 @@ void main() {
 
 				voxel_query(query);
 
-				proceed = proceed && query.id == 2;
+				proceed = proceed && query.id == BLOCK_ID_GRASS;
 
 				if(proceed) {
 					end = probe;
@@ -1,10 +1,10 @@
 #version 450
 
 #include "hexane.glsl"
-#include "noise.glsl"
+#include "hash.glsl"
 
 struct BuildNoisePush {
-	BufferId mersenne_id;
+	u32 seed;
 	ImageId noise_id;
 };
 
@@ -21,12 +21,7 @@ void main() {
 		return;	
 	}
 
-	u32 x = random(push_constant.mersenne_id);
-	u32 y = random(push_constant.mersenne_id);
-	u32 z = random(push_constant.mersenne_id);
-	u32 a = random(push_constant.mersenne_id);
-
-	imageStore(noise_img, i32vec3(gl_GlobalInvocationID), u32vec4(x, y, z, a));
+	imageStore(noise_img, i32vec3(gl_GlobalInvocationID), hash(push_constant.seed, gl_GlobalInvocationID));
 }
 
 #endif
@@ -22,7 +22,8 @@ vec3 calculate_position(i32vec3 noise_pos) {
 	
 	vec3 cell_size = imageSize(worley_img) / imageSize(noise_img);
 
-	u32vec3 random_numbers = u32vec3(imageLoad(noise_img, i32vec3(noise_pos)).rgb);
+	//wrap so the lattice tiles along with the image
+	u32vec3 random_numbers = u32vec3(imageLoad(noise_img, (noise_pos + imageSize(noise_img)) % imageSize(noise_img)).rgb);
 
 	vec3 cell_pos = noise_pos + vec3(random_numbers) / U32_MAX; 
 	cell_pos *= cell_size;
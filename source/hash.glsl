@@ -0,0 +1,19 @@
+//pcg4d from "hash functions for gpu rendering", jarzynski and olano
+//mirrored by math::noise::hash so the cpu can rebuild the noise lattice bit for bit
+u32vec4 hash(u32 seed, u32vec3 position) {
+	u32vec4 v = u32vec4(position, seed) * 1664525u + 1013904223u;
+
+	v.x += v.y * v.w;
+	v.y += v.z * v.x;
+	v.z += v.x * v.y;
+	v.w += v.y * v.z;
+
+	v ^= v >> 16u;
+
+	v.x += v.y * v.w;
+	v.y += v.z * v.x;
+	v.z += v.x * v.y;
+	v.w += v.y * v.z;
+
+	return v;
+}
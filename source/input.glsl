@@ -162,7 +162,7 @@ void main() {
 				VoxelData data = voxel_data(push_constant.region_id, u16(hit.block_hit.id));
 
 				ivec3 v = ivec3(hit.voxel_hit.destination + vec3(hit.voxel_hit.normal) * 0.5) % BLOCK_DETAIL;
-				data.voxels[v.x][v.y][v.z] = u16(3);
+				data.voxels[v.x][v.y][v.z] = u16(BLOCK_ID_STONE);
 
 				change.id = block_hashtable_insert(push_constant.region_id, data);
 				change.position = ivec3(hit.block_hit.destination - vec3(hit.block_hit.normal) * 0.5);
@@ -173,7 +173,7 @@ void main() {
 				VoxelData data = voxel_data(push_constant.region_id, u16(hit.block_hit.id));
 
 				ivec3 v = ivec3(hit.voxel_hit.destination - vec3(hit.voxel_hit.normal) * 0.5) % BLOCK_DETAIL;
-				data.voxels[v.x][v.y][v.z] = u16(0);
+				data.voxels[v.x][v.y][v.z] = u16(BLOCK_ID_VOID);
 
 				change.id = block_hashtable_insert(push_constant.region_id, data);
 				change.position = ivec3(hit.block_hit.destination - vec3(hit.block_hit.normal) * 0.5);
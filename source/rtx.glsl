@@ -274,7 +274,8 @@ bool ray_trace_drive(inout TraceState state) {
 	bool hit = false;
 	f32 smudge = 1e-1;
 
-	if(is_solid(u16(state.block_hit.id))) {
+	//block ids are hashtable slots rather than voxel ids, so only air is empty
+	if(u32(state.block_hit.id) != BLOCK_ID_AIR) {
 		RayState sub_state = state.ray_state;
 		sub_state.ray.medium = u16(state.block_hit.id);
 		RayHit sub_hit = state.block_hit;
@@ -379,15 +380,8 @@ PathInfo path_trace(Path path) {
 	if(success) {
 		u32 id = u32(hit.voxel_hit.id);
 		f32 noise_factor = 0.5;
-		if(id == 2) {
-			color.xyz *= mix(vec3(170, 255, 21) / 256, vec3(34, 139, 34) / 256, noise_factor);
-		}
-		if(id == 3) {
-			color.xyz *= mix(vec3(135) / 256, vec3(80) / 256, noise_factor);
-		}
-
-		if(id == 4) {
-			color.xyz *= mix(vec3(107, 84, 40) / 256, vec3(64, 41, 5) / 256, noise_factor);
+		if(id < BLOCK_COUNT) {
+			color.xyz *= mix(block_from[id], block_to[id], noise_factor);
 		}
 		
 		ivec3 normal = hit.voxel_hit.normal;
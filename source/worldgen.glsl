@@ -46,7 +46,7 @@ u16 world_gen(ivec3 world_position, BufferId region_id, ImageId perlin_id, Image
 			for(int x = 0; x < BLOCK_DETAIL; x++) {
 			for(int y = 0; y < BLOCK_DETAIL / 3; y++) {
 			for(int z = 0; z < BLOCK_DETAIL; z++) {
-				data.voxels[x][y][z] = u16(2);
+				data.voxels[x][y][z] = u16(BLOCK_ID_GRASS);
 			}
 			}
 			}
@@ -56,7 +56,7 @@ u16 world_gen(ivec3 world_position, BufferId region_id, ImageId perlin_id, Image
 			for(int x = 0; x < BLOCK_DETAIL; x++) {
 			for(int y = 0; y < BLOCK_DETAIL; y++) {
 			for(int z = 0; z < BLOCK_DETAIL; z++) {
-				data.voxels[x][y][z] = u16(4);
+				data.voxels[x][y][z] = u16(BLOCK_ID_DIRT);
 			}
 			}
 			}
@@ -66,7 +66,7 @@ u16 world_gen(ivec3 world_position, BufferId region_id, ImageId perlin_id, Image
 			for(int x = 0; x < BLOCK_DETAIL; x++) {
 			for(int y = 0; y < BLOCK_DETAIL; y++) {
 			for(int z = 0; z < BLOCK_DETAIL; z++) {
-				data.voxels[x][y][z] = u16(3);
+				data.voxels[x][y][z] = u16(BLOCK_ID_STONE);
 			}
 			}
 			}